@@ -2,7 +2,7 @@ extern crate __procon_math_traits as math_traits;
 
 use std::{
     marker::PhantomData,
-    ops::{Add, Bound, RangeBounds},
+    ops::{Add, Bound, Range, RangeBounds},
     usize,
 };
 
@@ -317,6 +317,248 @@ impl<Map: MapMonoid> LazySegTree<Map> {
     }
 }
 
+struct DynamicLazyNode<T, F> {
+    val: T,
+    lazy: F,
+    child: [usize; 2],
+}
+
+/// Lazy segment tree over `lo..hi` whose nodes are allocated only when touched.
+/// The domain is padded to a power of two, so `hi - lo` must not exceed `2^62`.
+pub struct DynamicLazySegTree<Map: MapMonoid> {
+    lo: i64,
+    // Number of positions, `hi - lo`; the tree works on offsets from `lo`.
+    n: u64,
+    log: usize,
+    // Value of an untouched node at each depth.
+    empty: Vec<<Map::M as Monoid>::T>,
+    // nodes[0] is a sentinel standing for an untouched subtree, nodes[1] is the root.
+    nodes: Vec<DynamicLazyNode<<Map::M as Monoid>::T, Map::F>>,
+}
+
+impl<Map: MapMonoid> DynamicLazySegTree<Map> {
+    pub fn new(range: Range<i64>) -> Self {
+        Self::filled(range, Map::id())
+    }
+
+    /// Every position in `range` starts out as `v`.
+    pub fn filled(range: Range<i64>, v: <Map::M as Monoid>::T) -> Self {
+        let n = range
+            .end
+            .checked_sub(range.start)
+            .filter(|&w| 0 < w && w <= 1 << 62)
+            .expect("the width of the range must be in 1..=2^62") as u64;
+        let log = n.next_power_of_two().trailing_zeros() as usize;
+        let mut empty = vec![v; log + 1];
+        for d in (0..log).rev() {
+            empty[d] = Map::op(&empty[d + 1], &empty[d + 1]);
+        }
+        let node = || DynamicLazyNode {
+            val: empty[0].clone(),
+            lazy: Map::map_id(),
+            child: [0, 0],
+        };
+        Self {
+            lo: range.start,
+            n,
+            log,
+            nodes: vec![node(), node()],
+            empty,
+        }
+    }
+
+    pub fn get(&mut self, idx: i64) -> <Map::M as Monoid>::T {
+        self.query(idx..=idx)
+    }
+
+    pub fn set(&mut self, idx: i64, v: <Map::M as Monoid>::T) {
+        let idx = self.offset(idx);
+        assert!(idx < self.n);
+        self.set_inner(1, 0, 0, idx, v);
+    }
+
+    pub fn query<R>(&mut self, range: R) -> <Map::M as Monoid>::T
+    where
+        R: RangeBounds<i64>,
+    {
+        let (l, r) = self.bounds(range);
+        self.query_inner(1, 0, 0, l, r)
+    }
+
+    pub fn apply_at(&mut self, idx: i64, f: Map::F) {
+        self.apply_range(idx..=idx, f)
+    }
+
+    pub fn apply_range<R>(&mut self, range: R, f: Map::F)
+    where
+        R: RangeBounds<i64>,
+    {
+        let (l, r) = self.bounds(range);
+        self.apply_range_inner(1, 0, 0, l, r, &f)
+    }
+
+    /// Largest `r` such that `g(op(a[l..r]))` holds. `g` must be monotone and `g(id)` true.
+    pub fn max_right<G>(&mut self, l: i64, g: G) -> i64
+    where
+        G: Fn(<Map::M as Monoid>::T) -> bool,
+    {
+        assert!(g(Map::id()));
+        let l = self.offset(l);
+        let mut acc = Map::id();
+        let r = self
+            .max_right_inner(1, 0, 0, l, &g, &mut acc)
+            .map_or(self.n, |r| r.min(self.n));
+        self.lo.wrapping_add(r as i64)
+    }
+}
+
+impl<Map: MapMonoid> DynamicLazySegTree<Map> {
+    /// Offset of position `i` from `lo`, for `lo <= i <= hi`.
+    fn offset(&self, i: i64) -> u64 {
+        assert!(self.lo <= i);
+        let o = i.wrapping_sub(self.lo) as u64;
+        assert!(o <= self.n);
+        o
+    }
+
+    fn bounds<R: RangeBounds<i64>>(&self, range: R) -> (u64, u64) {
+        use Bound::*;
+        let l = match range.start_bound() {
+            Unbounded => 0,
+            Included(&i) => self.offset(i),
+            Excluded(&i) => self.offset(i) + 1,
+        };
+        let r = match range.end_bound() {
+            Unbounded => self.n,
+            Included(&i) => self.offset(i) + 1,
+            Excluded(&i) => self.offset(i),
+        };
+        assert!(l <= r && r <= self.n);
+        (l, r)
+    }
+
+    fn width(&self, d: usize) -> u64 {
+        1 << (self.log - d)
+    }
+
+    fn push(&mut self, k: usize, d: usize) {
+        for c in 0..2 {
+            if self.nodes[k].child[c] == 0 {
+                self.nodes[k].child[c] = self.nodes.len();
+                self.nodes.push(DynamicLazyNode {
+                    val: self.empty[d + 1].clone(),
+                    lazy: Map::map_id(),
+                    child: [0, 0],
+                });
+            }
+            let f = self.nodes[k].lazy.clone();
+            self.apply(self.nodes[k].child[c], &f);
+        }
+        self.nodes[k].lazy = Map::map_id();
+    }
+
+    fn apply(&mut self, k: usize, f: &Map::F) {
+        let node = &mut self.nodes[k];
+        node.val = Map::map(f, &node.val);
+        node.lazy = Map::composite(f, &node.lazy);
+    }
+
+    fn update(&mut self, k: usize) {
+        let [a, b] = self.nodes[k].child;
+        self.nodes[k].val = Map::op(&self.nodes[a].val, &self.nodes[b].val);
+    }
+
+    fn set_inner(&mut self, k: usize, l: u64, d: usize, idx: u64, v: <Map::M as Monoid>::T) {
+        if d == self.log {
+            self.nodes[k].val = v;
+            return;
+        }
+        self.push(k, d);
+        let m = l + self.width(d + 1);
+        if idx < m {
+            self.set_inner(self.nodes[k].child[0], l, d + 1, idx, v);
+        } else {
+            self.set_inner(self.nodes[k].child[1], m, d + 1, idx, v);
+        }
+        self.update(k);
+    }
+
+    fn query_inner(
+        &mut self,
+        k: usize,
+        l: u64,
+        d: usize,
+        ql: u64,
+        qr: u64,
+    ) -> <Map::M as Monoid>::T {
+        let r = l + self.width(d);
+        if qr <= l || r <= ql {
+            return Map::id();
+        }
+        if ql <= l && r <= qr {
+            return self.nodes[k].val.clone();
+        }
+        self.push(k, d);
+        let m = l + self.width(d + 1);
+        let [a, b] = self.nodes[k].child;
+        let vl = self.query_inner(a, l, d + 1, ql, qr);
+        let vr = self.query_inner(b, m, d + 1, ql, qr);
+        Map::op(&vl, &vr)
+    }
+
+    fn apply_range_inner(&mut self, k: usize, l: u64, d: usize, ql: u64, qr: u64, f: &Map::F) {
+        let r = l + self.width(d);
+        if qr <= l || r <= ql {
+            return;
+        }
+        if ql <= l && r <= qr {
+            self.apply(k, f);
+            return;
+        }
+        self.push(k, d);
+        let m = l + self.width(d + 1);
+        let [a, b] = self.nodes[k].child;
+        self.apply_range_inner(a, l, d + 1, ql, qr, f);
+        self.apply_range_inner(b, m, d + 1, ql, qr, f);
+        self.update(k);
+    }
+
+    fn max_right_inner<G>(
+        &mut self,
+        k: usize,
+        l: u64,
+        d: usize,
+        ql: u64,
+        g: &G,
+        acc: &mut <Map::M as Monoid>::T,
+    ) -> Option<u64>
+    where
+        G: Fn(<Map::M as Monoid>::T) -> bool,
+    {
+        let r = l + self.width(d);
+        if r <= ql {
+            return None;
+        }
+        if ql <= l {
+            let v = Map::op(acc, &self.nodes[k].val);
+            if g(v.clone()) {
+                *acc = v;
+                return None;
+            }
+            if d == self.log {
+                return Some(l);
+            }
+        }
+        self.push(k, d);
+        let m = l + self.width(d + 1);
+        let [a, b] = self.nodes[k].child;
+        if let Some(r) = self.max_right_inner(a, l, d + 1, ql, g, acc) {
+            return Some(r);
+        }
+        self.max_right_inner(b, m, d + 1, ql, g, acc)
+    }
+}
+
 pub struct MaxAdd<T>(PhantomData<fn() -> T>);
 impl<T> MapMonoid for MaxAdd<T>
 where
@@ -387,6 +629,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::Rng;
 
     #[test]
     fn test_lazy_segtree_range_add_range_maximum() {
@@ -422,4 +665,110 @@ mod tests {
             }
         }
     }
+
+    /// (sum, length)
+    struct SumLen;
+    impl Monoid for SumLen {
+        type T = (i64, i64);
+        fn id() -> (i64, i64) {
+            (0, 0)
+        }
+        fn op(a: &(i64, i64), b: &(i64, i64)) -> (i64, i64) {
+            (a.0 + b.0, a.1 + b.1)
+        }
+    }
+
+    struct SumAdd;
+    impl MapMonoid for SumAdd {
+        type M = SumLen;
+        type F = i64;
+
+        fn map_id() -> i64 {
+            0
+        }
+
+        fn map(f: &i64, x: &(i64, i64)) -> (i64, i64) {
+            (x.0 + f * x.1, x.1)
+        }
+
+        fn composite(f: &i64, g: &i64) -> i64 {
+            f + g
+        }
+    }
+
+    #[test]
+    fn test_dynamic_lazy_segtree() {
+        let mut rng = rand::thread_rng();
+        let (lo, hi) = (-13, 17);
+        let mut v = vec![0i64; (hi - lo) as usize];
+        let mut seg = DynamicLazySegTree::<SumAdd>::filled(lo..hi, (0, 1));
+        let mut max = DynamicLazySegTree::<MaxAdd<i64>>::filled(lo..hi, 0);
+        for _ in 0..100 {
+            let (l, r) = (rng.gen_range(lo, hi + 1), rng.gen_range(lo, hi + 1));
+            let (l, r) = (l.min(r), l.max(r));
+            let at = |i: i64| (i - lo) as usize;
+            match rng.gen_range(0, 3) {
+                0 => {
+                    let x = rng.gen_range(0, 10);
+                    v[at(l)..at(r)].iter_mut().for_each(|v| *v += x);
+                    seg.apply_range(l..r, x);
+                    max.apply_range(l..r, x);
+                }
+                1 if l < hi => {
+                    let x = rng.gen_range(0, 10);
+                    v[at(l)] = x;
+                    seg.set(l, (x, 1));
+                    max.set(l, x);
+                }
+                _ => {
+                    let sum = v[at(l)..at(r)].iter().sum::<i64>();
+                    assert_eq!(seg.query(l..r), (sum, r - l));
+                    if l < r {
+                        assert_eq!(max.query(l..r), *v[at(l)..at(r)].iter().max().unwrap());
+                        assert_eq!(seg.get(l), (v[at(l)], 1));
+                    }
+                    let k = rng.gen_range(0, 50);
+                    let expected = (l..=hi)
+                        .take_while(|&r| v[at(l)..at(r)].iter().sum::<i64>() <= k)
+                        .last()
+                        .unwrap();
+                    assert_eq!(seg.max_right(l, |s| s.0 <= k), expected);
+                }
+            }
+        }
+
+        let big = 1_000_000_000_000_000_000;
+        let mut seg = DynamicLazySegTree::<SumAdd>::filled(-big..big, (0, 1));
+        seg.apply_range(..0, 1);
+        seg.apply_at(5, 3);
+        assert_eq!(seg.query(..), (big + 3, 2 * big));
+        assert_eq!(seg.query(-10..10), (13, 20));
+        assert_eq!(seg.max_right(-big, |s| s.0 < big), -1);
+        assert_eq!(seg.max_right(-1, |s| s.0 < 4), 5);
+        assert_eq!(seg.max_right(0, |s| s.0 < 4), big);
+    }
+
+    #[test]
+    fn test_dynamic_lazy_segtree_near_max() {
+        let (lo, hi) = (9_000_000_000_000_000_000, 9_200_000_000_000_000_000);
+        let mut seg = DynamicLazySegTree::<MaxAdd<i64>>::filled(lo..hi, 0);
+        seg.set(lo, 5);
+        seg.apply_range(hi - 10.., 7);
+        seg.apply_at(hi - 1, 1);
+        assert_eq!(seg.query(..), 8);
+        assert_eq!(seg.query(lo..hi - 10), 5);
+        assert_eq!(seg.get(hi - 2), 7);
+        assert_eq!(seg.max_right(lo, |m| m < 8), hi - 1);
+
+        let mut seg = DynamicLazySegTree::<SumAdd>::filled(i64::MAX - 10..i64::MAX, (0, 1));
+        seg.apply_range(i64::MAX - 3..=i64::MAX - 1, 2);
+        assert_eq!(seg.query(..), (6, 10));
+        assert_eq!(seg.max_right(i64::MAX - 10, |s| s.0 <= 6), i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "width")]
+    fn test_dynamic_lazy_segtree_too_wide() {
+        DynamicLazySegTree::<SumAdd>::new(i64::MIN..i64::MAX);
+    }
 }
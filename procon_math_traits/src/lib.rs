@@ -33,6 +33,11 @@ pub trait Monoid {
     fn op(lhs: &Self::T, rhs: &Self::T) -> Self::T;
 }
 
+/// A monoid whose `op` is commutative and every element has an inverse.
+pub trait AbelianGroup: Monoid {
+    fn inv(x: &Self::T) -> Self::T;
+}
+
 pub trait Bounded: BoundedBelow + BoundedAbove {}
 
 impl<T: BoundedBelow + BoundedAbove + ?Sized> Bounded for T {}
@@ -326,6 +331,15 @@ where
     }
 }
 
+impl<T> AbelianGroup for Additive<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Zero,
+{
+    fn inv(x: &T) -> T {
+        T::zero() - *x
+    }
+}
+
 pub struct Multiplicative<T>(PhantomData<fn() -> T>);
 impl<T> Monoid for Multiplicative<T>
 where
@@ -396,3 +410,9 @@ impl<T: PrimitiveInteger> Monoid for Xor<T> {
         *a ^ *b
     }
 }
+
+impl<T: PrimitiveInteger> AbelianGroup for Xor<T> {
+    fn inv(x: &T) -> T {
+        *x
+    }
+}
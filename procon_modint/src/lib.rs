@@ -290,7 +290,7 @@ pub trait ModuloPrimitive: Clone + Copy {
 
 macro_rules! define_modulo_primitive {
     ($name:ident, $mod:expr, $proot:expr) => {
-        #[derive(Debug, Clone, Copy)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
         pub struct $name;
         impl ModuloPrimitive for $name {
             fn modulo() -> i64 {
@@ -1,72 +1,471 @@
+extern crate __procon_math_traits as math_traits;
+extern crate __procon_segtree as segtree;
+
+use math_traits::Monoid;
+use segtree::SegTree;
 use std::{
+    cell::Cell,
+    cmp::{min, Ordering},
+    fmt, hash,
+    iter::FromIterator,
     marker::PhantomData,
     ops::{Bound, RangeBounds},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-type Num = u128;
+pub trait Hash: Copy + Eq + Ord + hash::Hash + fmt::Debug {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// The `k`-th of a family of independent bases drawn from the seed given to `set_seed`.
+    fn nth_base(k: u64) -> Self;
+    fn base() -> Self {
+        Self::nth_base(0)
+    }
+    fn from_u64(v: u64) -> Self;
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+}
+
+thread_local! {
+    static SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Fixes the seed the bases are drawn from. A time-based seed is used until this is called.
+/// Hashes are only comparable between `RollingHash`es built under the same seed.
+/// The seed is per thread: hashes built on different threads are only comparable if each
+/// thread called `set_seed` with the same value first.
+pub fn set_seed(seed: u64) {
+    SEED.with(|s| s.set(Some(seed)));
+}
+
+fn seed() -> u64 {
+    SEED.with(|s| match s.get() {
+        Some(seed) => seed,
+        None => {
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64;
+            s.set(Some(seed));
+            seed
+        }
+    })
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const MOD61: u64 = (1 << 61) - 1;
+const MOD32: u64 = 4_294_967_291;
+
+fn mul61(a: u64, b: u64) -> u64 {
+    let t = a as u128 * b as u128;
+    let t = (t >> 61) as u64 + (t as u64 & MOD61);
+    if t >= MOD61 {
+        t - MOD61
+    } else {
+        t
+    }
+}
 
-pub trait Hash {
-    fn modulo() -> Num;
-    fn base() -> Num;
+fn mul32(a: u64, b: u64) -> u64 {
+    a * b % MOD32
 }
 
 macro_rules! define_hash {
-    ($id:ident, $base:tt, $modulo:tt) => {
-        #[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
-        pub struct $id;
+    ($id:ident, $modulo:expr, $mul:expr) => {
+        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug)]
+        pub struct $id(u64);
         impl Hash for $id {
-            fn modulo() -> Num {
-                $modulo
+            fn zero() -> Self {
+                $id(0)
+            }
+            fn one() -> Self {
+                $id(1)
+            }
+            fn nth_base(k: u64) -> Self {
+                let x = splitmix64(seed() ^ $modulo ^ splitmix64(k));
+                $id(2 + x % ($modulo - 3))
+            }
+            fn from_u64(v: u64) -> Self {
+                $id(v % $modulo)
+            }
+            fn add(self, rhs: Self) -> Self {
+                let v = self.0 + rhs.0;
+                $id(if v >= $modulo { v - $modulo } else { v })
             }
-            fn base() -> Num {
-                $base
+            fn sub(self, rhs: Self) -> Self {
+                $id(if self.0 >= rhs.0 {
+                    self.0 - rhs.0
+                } else {
+                    self.0 + $modulo - rhs.0
+                })
+            }
+            fn mul(self, rhs: Self) -> Self {
+                $id($mul(self.0, rhs.0))
             }
         }
     };
 }
 
-define_hash!(Hash61, 1_024_578_101, 2_305_843_009_213_693_951);
+define_hash!(Hash61, MOD61, mul61);
+define_hash!(Hash32, MOD32, mul32);
+
+/// Hash over two independent moduli, 2^61 - 1 and 4294967291.
+pub type DoubleHash = (Hash61, Hash32);
+
+impl<A: Hash, B: Hash> Hash for (A, B) {
+    fn zero() -> Self {
+        (A::zero(), B::zero())
+    }
+    fn one() -> Self {
+        (A::one(), B::one())
+    }
+    fn nth_base(k: u64) -> Self {
+        (A::nth_base(k), B::nth_base(k))
+    }
+    fn from_u64(v: u64) -> Self {
+        (A::from_u64(v), B::from_u64(v))
+    }
+    fn add(self, rhs: Self) -> Self {
+        (self.0.add(rhs.0), self.1.add(rhs.1))
+    }
+    fn sub(self, rhs: Self) -> Self {
+        (self.0.sub(rhs.0), self.1.sub(rhs.1))
+    }
+    fn mul(self, rhs: Self) -> Self {
+        (self.0.mul(rhs.0), self.1.mul(rhs.1))
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct HashValue<H>(H);
+
+fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    use Bound::*;
+    let l = match range.start_bound() {
+        Unbounded => 0,
+        Included(&i) => i,
+        Excluded(&i) => i + 1,
+    };
+    let r = match range.end_bound() {
+        Unbounded => len,
+        Included(&i) => i + 1,
+        Excluded(&i) => i,
+    };
+    (l, r)
+}
 
 pub struct RollingHash<H> {
     size: usize,
-    pow: Vec<Num>,
-    hash: Vec<Num>,
-    __phantom: PhantomData<fn() -> H>,
+    base: H,
+    code: Vec<u64>,
+    pow: Vec<H>,
+    hash: Vec<H>,
 }
 
-impl<H: Hash, B: AsRef<[u8]>> From<B> for RollingHash<H> {
-    fn from(s: B) -> RollingHash<H> {
-        let s = s.as_ref();
-        let n = s.len();
-        let mut pow = vec![1; n + 1];
-        let mut hash = vec![0; n + 1];
-        for i in 0..n {
-            pow[i + 1] = pow[i] * H::base() % H::modulo();
-            hash[i + 1] = (hash[i] * H::base() + s[i] as Num) % H::modulo();
+/// Sequence elements that can be hashed, through an injective and order-preserving code.
+pub trait HashSymbol {
+    fn code(&self) -> u64;
+}
+
+macro_rules! impl_hash_symbol {
+    ($($t:ty)*) => {$(
+        impl HashSymbol for $t {
+            fn code(&self) -> u64 {
+                *self as u64
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_hash_symbol_signed {
+    ($($t:ty)*) => {$(
+        impl HashSymbol for $t {
+            fn code(&self) -> u64 {
+                (*self as i64 as u64) ^ (1 << 63)
+            }
+        }
+    )*};
+}
+
+impl_hash_symbol!(u8 u16 u32 u64 usize char bool);
+impl_hash_symbol_signed!(i8 i16 i32 i64 isize);
+
+impl<T: HashSymbol> HashSymbol for &T {
+    fn code(&self) -> u64 {
+        (*self).code()
+    }
+}
+
+// 64-bit codes cannot be embedded injectively into the field, so they are mixed with a
+// seeded key first; a collision, or a symbol embedding to 0, cannot be targeted.
+fn symbol_key() -> u64 {
+    splitmix64(!seed())
+}
+
+fn embed<H: Hash>(key: u64, code: u64) -> H {
+    H::from_u64(splitmix64(code ^ key))
+}
+
+impl<H: Hash, T: HashSymbol> FromIterator<T> for RollingHash<H> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> RollingHash<H> {
+        let (base, key) = (H::base(), symbol_key());
+        let mut pow = vec![H::one()];
+        let mut hash = vec![H::zero()];
+        let code = iter.into_iter().map(|c| c.code()).collect::<Vec<_>>();
+        for &c in &code {
+            hash.push(hash[hash.len() - 1].mul(base).add(embed(key, c)));
+            pow.push(pow[pow.len() - 1].mul(base));
         }
         Self {
-            size: n,
+            size: code.len(),
+            base,
+            code,
             pow,
             hash,
-            __phantom: PhantomData,
         }
     }
 }
 
+impl<H: Hash, B: AsRef<[u8]>> From<B> for RollingHash<H> {
+    fn from(s: B) -> RollingHash<H> {
+        s.as_ref().iter().collect()
+    }
+}
+
 impl<H: Hash> RollingHash<H> {
-    pub fn hash<R: RangeBounds<usize>>(&self, range: R) -> Num {
-        use Bound::*;
-        let l = match range.start_bound() {
-            Unbounded => 0,
-            Included(&i) => i,
-            Excluded(&i) => i + 1,
-        };
-        let r = match range.end_bound() {
-            Unbounded => self.size,
-            Included(&i) => i,
-            Excluded(&i) => i - 1,
-        };
-        (self.hash[r] + H::modulo() - (self.hash[l] * self.pow[r - l]) % H::modulo()) % H::modulo()
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    fn pow(&self, e: usize) -> H {
+        if e < self.pow.len() {
+            return self.pow[e];
+        }
+        let (mut res, mut cur, mut e) = (H::one(), self.base, e);
+        while e > 0 {
+            if e & 1 == 1 {
+                res = res.mul(cur);
+            }
+            cur = cur.mul(cur);
+            e >>= 1;
+        }
+        res
+    }
+
+    pub fn hash<R: RangeBounds<usize>>(&self, range: R) -> HashValue<H> {
+        let (l, r) = bounds(range, self.size);
+        HashValue(self.hash[r].sub(self.hash[l].mul(self.pow[r - l])))
+    }
+
+    /// Hash of the concatenation of a string hashed to `h1` and a string of length `len2`
+    /// hashed to `h2`.
+    pub fn concat(&self, h1: HashValue<H>, len2: usize, h2: HashValue<H>) -> HashValue<H> {
+        HashValue(h1.0.mul(self.pow(len2)).add(h2.0))
+    }
+
+    /// Longest common prefix of `self[i..]` and `other[j..]`, by binary search on hashes.
+    pub fn lcp(&self, i: usize, other: &Self, j: usize) -> usize {
+        let (mut ok, mut ng) = (0, min(self.size - i, other.size - j) + 1);
+        while ng - ok > 1 {
+            let mid = (ok + ng) / 2;
+            if self.hash(i..i + mid) == other.hash(j..j + mid) {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        ok
+    }
+
+    /// Compares `self[a]` and `other[b]` lexicographically in the order of the symbols.
+    pub fn compare<R, S>(&self, a: R, other: &Self, b: S) -> Ordering
+    where
+        R: RangeBounds<usize>,
+        S: RangeBounds<usize>,
+    {
+        let (l1, r1) = bounds(a, self.size);
+        let (l2, r2) = bounds(b, other.size);
+        let len = min(r1 - l1, r2 - l2);
+        let k = min(self.lcp(l1, other, l2), len);
+        if k == len {
+            (r1 - l1).cmp(&(r2 - l2))
+        } else {
+            self.code[l1 + k].cmp(&other.code[l2 + k])
+        }
+    }
+}
+
+/// Monoid over `(hash, base^len)` pairs whose operation concatenates the hashed strings.
+pub struct HashConcat<H>(PhantomData<fn() -> H>);
+
+impl<H: Hash> Monoid for HashConcat<H> {
+    type T = (H, H);
+    fn id() -> Self::T {
+        (H::zero(), H::one())
+    }
+    fn op(a: &Self::T, b: &Self::T) -> Self::T {
+        (a.0.mul(b.1).add(b.0), a.1.mul(b.1))
+    }
+}
+
+/// Rolling hash supporting point updates, keeping the string and its reversal in segment trees.
+pub struct DynamicRollingHash<H: Hash> {
+    size: usize,
+    base: H,
+    key: u64,
+    fwd: SegTree<HashConcat<H>>,
+    rev: SegTree<HashConcat<H>>,
+}
+
+impl<H: Hash, T: HashSymbol> FromIterator<T> for DynamicRollingHash<H> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> DynamicRollingHash<H> {
+        let (base, key) = (H::base(), symbol_key());
+        let v = iter
+            .into_iter()
+            .map(|c| (embed(key, c.code()), base))
+            .collect::<Vec<_>>();
+        let size = v.len();
+        let rev = v.iter().rev().cloned().collect::<Vec<_>>();
+        Self {
+            size,
+            base,
+            key,
+            fwd: v.into(),
+            rev: rev.into(),
+        }
+    }
+}
+
+impl<H: Hash, B: AsRef<[u8]>> From<B> for DynamicRollingHash<H> {
+    fn from(s: B) -> DynamicRollingHash<H> {
+        s.as_ref().iter().collect()
+    }
+}
+
+impl<H: Hash> DynamicRollingHash<H> {
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn set<T: HashSymbol>(&mut self, idx: usize, c: T) {
+        let v = (embed(self.key, c.code()), self.base);
+        self.fwd.set(idx, v);
+        self.rev.set(self.size - 1 - idx, v);
+    }
+
+    /// Hash of the range, comparable with `RollingHash::hash` built under the same seed.
+    pub fn hash<R: RangeBounds<usize>>(&self, range: R) -> HashValue<H> {
+        let (l, r) = bounds(range, self.size);
+        HashValue(self.fwd.query(l..r).0)
+    }
+
+    pub fn is_palindrome<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        let (l, r) = bounds(range, self.size);
+        self.fwd.query(l..r).0 == self.rev.query(self.size - r..self.size - l).0
+    }
+}
+
+pub struct RollingHash2D<H> {
+    height: usize,
+    width: usize,
+    pow_row: Vec<H>,
+    pow_col: Vec<H>,
+    hash: Vec<Vec<H>>,
+}
+
+impl<'a, H: Hash, B: AsRef<[u8]>> From<&'a [B]> for RollingHash2D<H> {
+    fn from(grid: &'a [B]) -> RollingHash2D<H> {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |row| row.as_ref().len());
+        let (base_row, base_col) = (H::nth_base(1), H::nth_base(2));
+        let key = symbol_key();
+        let mut pow_row = vec![H::one(); height + 1];
+        for i in 0..height {
+            pow_row[i + 1] = pow_row[i].mul(base_row);
+        }
+        let mut pow_col = vec![H::one(); width + 1];
+        for j in 0..width {
+            pow_col[j + 1] = pow_col[j].mul(base_col);
+        }
+        let mut hash = vec![vec![H::zero(); width + 1]; height + 1];
+        for i in 0..height {
+            let row = grid[i].as_ref();
+            assert_eq!(row.len(), width);
+            for j in 0..width {
+                hash[i + 1][j + 1] = hash[i][j + 1]
+                    .mul(base_row)
+                    .add(hash[i + 1][j].mul(base_col))
+                    .sub(hash[i][j].mul(base_row).mul(base_col))
+                    .add(embed(key, row[j].code()));
+            }
+        }
+        Self {
+            height,
+            width,
+            pow_row,
+            pow_col,
+            hash,
+        }
+    }
+}
+
+impl<H: Hash> RollingHash2D<H> {
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Hash of the sub-rectangle `rows × cols`.
+    pub fn hash<R, C>(&self, rows: R, cols: C) -> HashValue<H>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let (r1, r2) = bounds(rows, self.height);
+        let (c1, c2) = bounds(cols, self.width);
+        let (pr, pc) = (self.pow_row[r2 - r1], self.pow_col[c2 - c1]);
+        HashValue(
+            self.hash[r2][c2]
+                .sub(self.hash[r1][c2].mul(pr))
+                .sub(self.hash[r2][c1].mul(pc))
+                .add(self.hash[r1][c1].mul(pr).mul(pc)),
+        )
+    }
+
+    /// Top-left corners of every occurrence of `pattern`.
+    pub fn find_subgrid(&self, pattern: &Self) -> Vec<(usize, usize)> {
+        let (h, w) = (pattern.height, pattern.width);
+        if h > self.height || w > self.width {
+            return vec![];
+        }
+        let ph = pattern.hash(.., ..);
+        let mut res = vec![];
+        for i in 0..=self.height - h {
+            for j in 0..=self.width - w {
+                if self.hash(i..i + h, j..j + w) == ph {
+                    res.push((i, j));
+                }
+            }
+        }
+        res
     }
 }
 
@@ -75,7 +474,7 @@ pub fn find_substring<H: Hash>(s: &RollingHash<H>, t: &RollingHash<H>) -> Vec<us
     let th = t.hash(..);
     let mut indices = vec![];
     for i in 0..=(s.size - t.size) {
-        let sh = s.hash(i..=i + t.size);
+        let sh = s.hash(i..i + t.size);
         if sh == th {
             indices.push(i);
         }
@@ -86,6 +485,7 @@ pub fn find_substring<H: Hash>(s: &RollingHash<H>, t: &RollingHash<H>) -> Vec<us
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::Rng;
 
     type RollingHash61 = RollingHash<Hash61>;
 
@@ -97,4 +497,154 @@ mod tests {
         let indices = find_substring(&s, &t);
         assert_eq!(indices, vec![12, 31, 47]);
     }
+
+    #[test]
+    fn test_seeded_base() {
+        set_seed(42);
+        let b1 = DoubleHash::base();
+        let a: RollingHash<DoubleHash> = "abracadabra".into();
+        set_seed(43);
+        assert_ne!(DoubleHash::base(), b1);
+        set_seed(42);
+        assert_eq!(DoubleHash::base(), b1);
+        let b: RollingHash<DoubleHash> = "cadabra".into();
+        assert_eq!(a.hash(4..), b.hash(..));
+        let c: RollingHash<DoubleHash> = "abra".into();
+        assert_eq!(a.hash(7..), c.hash(..));
+        assert_ne!(a.hash(6..), c.hash(..));
+        assert_eq!(find_substring(&a, &b), vec![4]);
+
+        let h = std::thread::spawn(|| {
+            set_seed(42);
+            RollingHash::<DoubleHash>::from("cadabra").hash(..)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(h, b.hash(..));
+    }
+
+    #[test]
+    fn test_lcp_compare_concat() {
+        let s = b"abracadabra";
+        let a: RollingHash61 = s.into();
+        let b: RollingHash61 = "cadabrab".into();
+        assert_eq!(a.lcp(0, &a, 7), 4);
+        assert_eq!(a.lcp(4, &b, 0), 7);
+        assert_eq!(a.compare(0..4, &a, 7..), Ordering::Equal);
+        assert_eq!(a.compare(.., &b, ..), Ordering::Less);
+        assert_eq!(a.compare(4.., &b, ..), Ordering::Less);
+        assert_eq!(a.compare(0..=2, &a, 0..2), Ordering::Greater);
+        let h = a.concat(a.hash(0..4), 7, a.hash(4..));
+        assert_eq!(h, a.hash(..));
+
+        let mut rotations = (0..s.len()).collect::<Vec<_>>();
+        let doubled: RollingHash61 = [&s[..], &s[..]].concat().into();
+        rotations.sort_by(|&i, &j| doubled.compare(i..i + s.len(), &doubled, j..j + s.len()));
+        let mut expected = (0..s.len()).collect::<Vec<_>>();
+        expected.sort_by_key(|&i| [&s[i..], &s[..i]].concat());
+        assert_eq!(rotations, expected);
+
+        let x: RollingHash61 = vec![-1i64].into_iter().collect();
+        let y: RollingHash61 = vec![0i64].into_iter().collect();
+        assert_eq!(x.compare(.., &y, ..), Ordering::Less);
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let gen = |rng: &mut rand::rngs::ThreadRng| {
+                let n = rng.gen_range(0, 5);
+                (0..n)
+                    .map(|_| [i64::MIN, -3, -1, 0, 2, i64::MAX][rng.gen_range(0, 6)])
+                    .collect::<Vec<_>>()
+            };
+            let (u, v) = (gen(&mut rng), gen(&mut rng));
+            let (x, y): (RollingHash61, RollingHash61) = (u.iter().collect(), v.iter().collect());
+            assert_eq!(x.compare(.., &y, ..), u.cmp(&v));
+        }
+    }
+
+    #[test]
+    fn test_generic_rolling_hash() {
+        let a: RollingHash61 = vec![0i64, 0, 5].into_iter().collect();
+        let b: RollingHash61 = vec![0i64, 5].into_iter().collect();
+        let c: RollingHash61 = vec![5i64].into_iter().collect();
+        assert_ne!(a.hash(..), b.hash(..));
+        assert_ne!(b.hash(..), c.hash(..));
+        assert_eq!(a.hash(1..), b.hash(..));
+        assert_eq!(a.hash(2..), c.hash(..));
+
+        let labels = [3usize, 1, 4, 1, 5, 9, 2, 6];
+        let d: RollingHash61 = labels.iter().collect();
+        assert_eq!(d.hash(1..2), d.hash(3..4));
+
+        let chars: RollingHash61 = "love".chars().collect();
+        let bytes: RollingHash61 = "love".into();
+        assert_eq!(chars.hash(..), bytes.hash(..));
+        let neg: RollingHash61 = vec![-1i64, -2].into_iter().collect();
+        assert_ne!(neg.hash(0..1), neg.hash(1..2));
+
+        // Codes that coincide modulo 2^61 - 1.
+        let hash = |v: &[i64]| v.iter().collect::<RollingHash61>().hash(..);
+        assert_ne!(hash(&[-1]), hash(&[7]));
+        let hash = |v: &[u64]| v.iter().collect::<RollingHash61>().hash(..);
+        assert_ne!(hash(&[0]), hash(&[(1 << 61) - 1]));
+        assert_ne!(hash(&[(1 << 61) - 2, 5]), hash(&[5]));
+    }
+
+    #[test]
+    fn test_dynamic_rolling_hash() {
+        set_seed(7);
+        let mut s = b"abcba".to_vec();
+        let mut d: DynamicRollingHash<DoubleHash> = (&s).into();
+        let r: RollingHash<DoubleHash> = (&s).into();
+        assert_eq!(d.hash(1..4), r.hash(1..4));
+        assert!(d.is_palindrome(..));
+        assert!(!d.is_palindrome(0..2));
+        d.set(1, b'x');
+        s[1] = b'x';
+        assert!(!d.is_palindrome(..));
+        assert!(d.is_palindrome(2..=2));
+        d.set(3, b'x');
+        s[3] = b'x';
+        assert!(d.is_palindrome(..));
+        let r: RollingHash<DoubleHash> = (&s).into();
+        for l in 0..=s.len() {
+            for k in l..=s.len() {
+                assert_eq!(d.hash(l..k), r.hash(l..k));
+                let pal = s[l..k].iter().eq(s[l..k].iter().rev());
+                assert_eq!(d.is_palindrome(l..k), pal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_hash_2d() {
+        let grid = ["abab", "baba", "abab"];
+        let g: RollingHash2D<Hash61> = grid[..].into();
+        let p: RollingHash2D<Hash61> = ["ab", "ba"][..].into();
+        assert_eq!(g.find_subgrid(&p), vec![(0, 0), (0, 2), (1, 1)]);
+        assert_eq!(g.hash(0..2, 0..2), g.hash(1..3, 1..3));
+        assert_ne!(g.hash(0..2, 0..2), g.hash(0..2, 1..3));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let (h, w) = (rng.gen_range(1, 12), rng.gen_range(1, 12));
+            let (ph, pw) = (rng.gen_range(1, h + 1), rng.gen_range(1, w + 1));
+            let grid = (0..h)
+                .map(|_| (0..w).map(|_| rng.gen_range(0, 2)).collect::<Vec<u8>>())
+                .collect::<Vec<_>>();
+            let pattern = (0..ph)
+                .map(|_| (0..pw).map(|_| rng.gen_range(0, 2)).collect::<Vec<u8>>())
+                .collect::<Vec<_>>();
+            let mut expected = vec![];
+            for i in 0..=h - ph {
+                for j in 0..=w - pw {
+                    if (0..ph).all(|k| grid[i + k][j..j + pw] == pattern[k][..]) {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            let g: RollingHash2D<DoubleHash> = grid[..].into();
+            let p: RollingHash2D<DoubleHash> = pattern[..].into();
+            assert_eq!(g.find_subgrid(&p), expected);
+        }
+    }
 }
@@ -1,13 +1,14 @@
 extern crate __procon_math_traits as math_traits;
 
-use math_traits::Monoid;
+use math_traits::{Additive, Monoid, PrimitiveInteger};
 
-use std::ops::{Bound, RangeBounds};
+use std::ops::{Bound, Range, RangeBounds};
 
 pub struct SegTree<M>
 where
     M: Monoid,
 {
+    n: usize,
     size: usize,
     log: usize,
     data: Vec<M::T>,
@@ -23,7 +24,12 @@ impl<M: Monoid> From<Vec<M::T>> for SegTree<M> {
         }
         let mut data = vec![M::id(); 2 * size];
         data[size..(size + v.len())].clone_from_slice(&v);
-        let mut st = Self { size, log, data };
+        let mut st = Self {
+            n: v.len(),
+            size,
+            log,
+            data,
+        };
         (0..size).rev().for_each(|i| st.update(i));
         st
     }
@@ -49,22 +55,24 @@ impl<M: Monoid> SegTree<M> {
     }
 
     fn query_inner(&self, mut l: usize, mut r: usize) -> M::T {
-        assert!(l <= r);
-        let mut ret = M::id();
+        assert!(l <= r && r <= self.n);
+        let mut vl = M::id();
+        let mut vr = M::id();
         l += self.size;
         r += self.size;
         while l < r {
             if (l & 1) > 0 {
-                ret = M::op(&ret, &self.data[l]);
+                vl = M::op(&vl, &self.data[l]);
                 l += 1;
             }
             if (r & 1) > 0 {
-                ret = M::op(&ret, &self.data[r - 1]);
+                r -= 1;
+                vr = M::op(&self.data[r], &vr);
             }
             l >>= 1;
             r >>= 1;
         }
-        ret
+        M::op(&vl, &vr)
     }
 
     pub fn query<R>(&self, range: R) -> M::T
@@ -78,18 +86,504 @@ impl<M: Monoid> SegTree<M> {
             Excluded(&i) => i + 1,
         };
         let end = match range.end_bound() {
-            Unbounded => self.size,
+            Unbounded => self.n,
             Included(&i) => i + 1,
             Excluded(&i) => i,
         };
         self.query_inner(start, end)
     }
+
+    /// Largest `r` such that `g(op(a[l..r]))` holds. `g` must be monotone and `g(id)` true.
+    pub fn max_right<G>(&self, mut l: usize, g: G) -> usize
+    where
+        G: Fn(M::T) -> bool,
+    {
+        assert!(g(M::id()));
+        assert!(l <= self.n);
+        if l == self.n {
+            return self.n;
+        }
+        l += self.size;
+        let mut mv = M::id();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            if !g(M::op(&mv, &self.data[l])) {
+                while l < self.size {
+                    l *= 2;
+                    let res = M::op(&mv, &self.data[l]);
+                    if g(res.clone()) {
+                        mv = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            mv = M::op(&mv, &self.data[l]);
+            l += 1;
+            if l.is_power_of_two() {
+                return self.n;
+            }
+        }
+    }
+
+    /// Smallest `l` such that `g(op(a[l..r]))` holds. `g` must be monotone and `g(id)` true.
+    pub fn min_left<G>(&self, mut r: usize, g: G) -> usize
+    where
+        G: Fn(M::T) -> bool,
+    {
+        assert!(g(M::id()));
+        assert!(r <= self.n);
+        if r == 0 {
+            return 0;
+        }
+        r += self.size;
+        let mut mv = M::id();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            if !g(M::op(&self.data[r], &mv)) {
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let res = M::op(&self.data[r], &mv);
+                    if g(res.clone()) {
+                        mv = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            mv = M::op(&self.data[r], &mv);
+            if r.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+}
+
+impl<T: PrimitiveInteger> SegTree<Additive<T>> {
+    /// Smallest `i` with `a[0] + ... + a[i] >= k`, assuming every element is non-negative.
+    pub fn kth(&self, k: T) -> Option<usize> {
+        let i = if k <= T::zero() {
+            0
+        } else {
+            self.max_right(0, |s| s < k)
+        };
+        if i < self.n {
+            Some(i)
+        } else {
+            None
+        }
+    }
+}
+
+struct DynamicNode<T> {
+    val: T,
+    child: [usize; 2],
+}
+
+/// Segment tree over `lo..hi` whose nodes are allocated only when touched.
+/// The domain is padded to a power of two, so `hi - lo` must not exceed `2^62`.
+pub struct DynamicSegTree<M: Monoid> {
+    lo: i64,
+    // Number of positions, `hi - lo`; the tree works on offsets from `lo`.
+    n: u64,
+    log: usize,
+    // Value of an untouched node at each depth.
+    empty: Vec<M::T>,
+    // nodes[0] is a sentinel standing for an untouched subtree, nodes[1] is the root.
+    nodes: Vec<DynamicNode<M::T>>,
+}
+
+impl<M: Monoid> DynamicSegTree<M> {
+    pub fn new(range: Range<i64>) -> Self {
+        Self::filled(range, M::id())
+    }
+
+    /// Every position in `range` starts out as `v`.
+    pub fn filled(range: Range<i64>, v: M::T) -> Self {
+        let n = range
+            .end
+            .checked_sub(range.start)
+            .filter(|&w| 0 < w && w <= 1 << 62)
+            .expect("the width of the range must be in 1..=2^62") as u64;
+        let log = n.next_power_of_two().trailing_zeros() as usize;
+        let mut empty = vec![v; log + 1];
+        for d in (0..log).rev() {
+            empty[d] = M::op(&empty[d + 1], &empty[d + 1]);
+        }
+        let node = || DynamicNode {
+            val: empty[0].clone(),
+            child: [0, 0],
+        };
+        Self {
+            lo: range.start,
+            n,
+            log,
+            nodes: vec![node(), node()],
+            empty,
+        }
+    }
+
+    pub fn get(&self, idx: i64) -> M::T {
+        let idx = self.offset(idx);
+        assert!(idx < self.n);
+        let (mut k, mut l) = (1, 0);
+        for d in 0..self.log {
+            let m = l + self.width(d + 1);
+            k = if idx < m {
+                self.nodes[k].child[0]
+            } else {
+                l = m;
+                self.nodes[k].child[1]
+            };
+        }
+        self.value(k, self.log).clone()
+    }
+
+    pub fn set(&mut self, idx: i64, v: M::T) {
+        let idx = self.offset(idx);
+        assert!(idx < self.n);
+        let (mut k, mut l) = (1, 0);
+        let mut path = vec![];
+        for d in 0..self.log {
+            path.push(k);
+            let m = l + self.width(d + 1);
+            let c = if idx < m {
+                0
+            } else {
+                l = m;
+                1
+            };
+            if self.nodes[k].child[c] == 0 {
+                self.nodes[k].child[c] = self.nodes.len();
+                self.nodes.push(DynamicNode {
+                    val: self.empty[d + 1].clone(),
+                    child: [0, 0],
+                });
+            }
+            k = self.nodes[k].child[c];
+        }
+        self.nodes[k].val = v;
+        for (d, &k) in path.iter().enumerate().rev() {
+            let [a, b] = self.nodes[k].child;
+            self.nodes[k].val = M::op(self.value(a, d + 1), self.value(b, d + 1));
+        }
+    }
+
+    pub fn query<R>(&self, range: R) -> M::T
+    where
+        R: RangeBounds<i64>,
+    {
+        let (l, r) = self.bounds(range);
+        self.query_inner(1, 0, 0, l, r)
+    }
+
+    /// Largest `r` such that `g(op(a[l..r]))` holds. `g` must be monotone and `g(id)` true.
+    pub fn max_right<G>(&self, l: i64, g: G) -> i64
+    where
+        G: Fn(M::T) -> bool,
+    {
+        assert!(g(M::id()));
+        let l = self.offset(l);
+        let mut acc = M::id();
+        let r = self
+            .max_right_inner(1, 0, 0, l, &g, &mut acc)
+            .map_or(self.n, |r| r.min(self.n));
+        self.lo.wrapping_add(r as i64)
+    }
+}
+
+impl<M: Monoid> DynamicSegTree<M> {
+    /// Offset of position `i` from `lo`, for `lo <= i <= hi`.
+    fn offset(&self, i: i64) -> u64 {
+        assert!(self.lo <= i);
+        let o = i.wrapping_sub(self.lo) as u64;
+        assert!(o <= self.n);
+        o
+    }
+
+    fn bounds<R: RangeBounds<i64>>(&self, range: R) -> (u64, u64) {
+        use Bound::*;
+        let l = match range.start_bound() {
+            Unbounded => 0,
+            Included(&i) => self.offset(i),
+            Excluded(&i) => self.offset(i) + 1,
+        };
+        let r = match range.end_bound() {
+            Unbounded => self.n,
+            Included(&i) => self.offset(i) + 1,
+            Excluded(&i) => self.offset(i),
+        };
+        assert!(l <= r && r <= self.n);
+        (l, r)
+    }
+
+    fn width(&self, d: usize) -> u64 {
+        1 << (self.log - d)
+    }
+
+    fn value(&self, k: usize, d: usize) -> &M::T {
+        if k == 0 {
+            &self.empty[d]
+        } else {
+            &self.nodes[k].val
+        }
+    }
+
+    fn query_inner(&self, k: usize, l: u64, d: usize, ql: u64, qr: u64) -> M::T {
+        let r = l + self.width(d);
+        if qr <= l || r <= ql {
+            return M::id();
+        }
+        if ql <= l && r <= qr {
+            return self.value(k, d).clone();
+        }
+        let m = l + self.width(d + 1);
+        let [a, b] = self.nodes[k].child;
+        M::op(
+            &self.query_inner(a, l, d + 1, ql, qr),
+            &self.query_inner(b, m, d + 1, ql, qr),
+        )
+    }
+
+    fn max_right_inner<G>(
+        &self,
+        k: usize,
+        l: u64,
+        d: usize,
+        ql: u64,
+        g: &G,
+        acc: &mut M::T,
+    ) -> Option<u64>
+    where
+        G: Fn(M::T) -> bool,
+    {
+        let r = l + self.width(d);
+        if r <= ql {
+            return None;
+        }
+        if ql <= l {
+            let v = M::op(acc, self.value(k, d));
+            if g(v.clone()) {
+                *acc = v;
+                return None;
+            }
+            if d == self.log {
+                return Some(l);
+            }
+        }
+        let m = l + self.width(d + 1);
+        let [a, b] = self.nodes[k].child;
+        if let Some(r) = self.max_right_inner(a, l, d + 1, ql, g, acc) {
+            return Some(r);
+        }
+        self.max_right_inner(b, m, d + 1, ql, g, acc)
+    }
+}
+
+struct PersistentNode<T> {
+    val: T,
+    child: [usize; 2],
+}
+
+/// Segment tree whose `set` creates a new version sharing structure with the old one.
+/// Versions are numbered from 0, the initial array.
+pub struct PersistentSegTree<M: Monoid> {
+    n: usize,
+    nodes: Vec<PersistentNode<M::T>>,
+    roots: Vec<usize>,
+}
+
+impl<M: Monoid> From<Vec<M::T>> for PersistentSegTree<M> {
+    fn from(v: Vec<M::T>) -> Self {
+        let mut st = Self {
+            n: v.len(),
+            nodes: vec![],
+            roots: vec![],
+        };
+        let root = st.build(&v);
+        st.roots.push(root);
+        st
+    }
+}
+
+impl<M: Monoid> PersistentSegTree<M> {
+    pub fn new(size: usize) -> PersistentSegTree<M> {
+        vec![M::id(); size].into()
+    }
+
+    /// The most recently created version.
+    pub fn latest(&self) -> usize {
+        self.roots.len() - 1
+    }
+
+    pub fn get(&self, version: usize, idx: usize) -> M::T {
+        assert!(idx < self.n);
+        let (mut k, mut l, mut r) = (self.roots[version], 0, self.n);
+        while r - l > 1 {
+            let m = (l + r) / 2;
+            k = if idx < m {
+                r = m;
+                self.nodes[k].child[0]
+            } else {
+                l = m;
+                self.nodes[k].child[1]
+            };
+        }
+        self.nodes[k].val.clone()
+    }
+
+    /// Sets `a[idx] = v` on top of `version` and returns the new version.
+    pub fn set(&mut self, version: usize, idx: usize, v: M::T) -> usize {
+        assert!(idx < self.n);
+        let root = self.set_inner(self.roots[version], 0, self.n, idx, v);
+        self.roots.push(root);
+        self.latest()
+    }
+
+    pub fn query<R>(&self, version: usize, range: R) -> M::T
+    where
+        R: RangeBounds<usize>,
+    {
+        use Bound::*;
+        let start = match range.start_bound() {
+            Unbounded => 0,
+            Included(&i) => i,
+            Excluded(&i) => i + 1,
+        };
+        let end = match range.end_bound() {
+            Unbounded => self.n,
+            Included(&i) => i + 1,
+            Excluded(&i) => i,
+        };
+        assert!(start <= end && end <= self.n);
+        self.query_inner(self.roots[version], 0, self.n, start, end)
+    }
+
+    fn push(&mut self, val: M::T, child: [usize; 2]) -> usize {
+        self.nodes.push(PersistentNode { val, child });
+        self.nodes.len() - 1
+    }
+
+    fn build(&mut self, v: &[M::T]) -> usize {
+        if v.len() <= 1 {
+            let val = v.first().cloned().unwrap_or_else(M::id);
+            return self.push(val, [0, 0]);
+        }
+        let (l, r) = v.split_at(v.len() / 2);
+        let (a, b) = (self.build(l), self.build(r));
+        let val = M::op(&self.nodes[a].val, &self.nodes[b].val);
+        self.push(val, [a, b])
+    }
+
+    fn set_inner(&mut self, k: usize, l: usize, r: usize, idx: usize, v: M::T) -> usize {
+        if r - l == 1 {
+            return self.push(v, [0, 0]);
+        }
+        let m = (l + r) / 2;
+        let [mut a, mut b] = self.nodes[k].child;
+        if idx < m {
+            a = self.set_inner(a, l, m, idx, v);
+        } else {
+            b = self.set_inner(b, m, r, idx, v);
+        }
+        let val = M::op(&self.nodes[a].val, &self.nodes[b].val);
+        self.push(val, [a, b])
+    }
+
+    fn query_inner(&self, k: usize, l: usize, r: usize, ql: usize, qr: usize) -> M::T {
+        if qr <= l || r <= ql {
+            return M::id();
+        }
+        if ql <= l && r <= qr {
+            return self.nodes[k].val.clone();
+        }
+        let m = (l + r) / 2;
+        let [a, b] = self.nodes[k].child;
+        M::op(
+            &self.query_inner(a, l, m, ql, qr),
+            &self.query_inner(b, m, r, ql, qr),
+        )
+    }
+}
+
+/// k-th smallest element of `a[l..r]` from prefix versions of a persistent counting tree.
+/// O(N log N) construction, O(log N) per query.
+pub struct RangeKthSmallest<T> {
+    sorted: Vec<T>,
+    tree: PersistentSegTree<Additive<usize>>,
+}
+
+impl<T: Ord + Clone> RangeKthSmallest<T> {
+    pub fn new(a: &[T]) -> Self {
+        let n = a.len();
+        let mut order = (0..n).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| a[i].cmp(&a[j]));
+        let mut rank = vec![0; n];
+        for (r, &i) in order.iter().enumerate() {
+            rank[i] = r;
+        }
+        let mut tree = PersistentSegTree::new(n);
+        for (i, &r) in rank.iter().enumerate() {
+            tree.set(i, r, 1);
+        }
+        Self {
+            sorted: order.into_iter().map(|i| a[i].clone()).collect(),
+            tree,
+        }
+    }
+
+    /// The `k`-th (0-indexed) smallest element of `a[range]`.
+    pub fn kth<R>(&self, range: R, mut k: usize) -> Option<&T>
+    where
+        R: RangeBounds<usize>,
+    {
+        use Bound::*;
+        let start = match range.start_bound() {
+            Unbounded => 0,
+            Included(&i) => i,
+            Excluded(&i) => i + 1,
+        };
+        let end = match range.end_bound() {
+            Unbounded => self.sorted.len(),
+            Included(&i) => i + 1,
+            Excluded(&i) => i,
+        };
+        assert!(start <= end && end <= self.sorted.len());
+        let nodes = &self.tree.nodes;
+        let (mut u, mut v) = (self.tree.roots[start], self.tree.roots[end]);
+        if nodes[v].val - nodes[u].val <= k {
+            return None;
+        }
+        let (mut l, mut r) = (0, self.sorted.len());
+        while r - l > 1 {
+            let m = (l + r) / 2;
+            let ([ul, ur], [vl, vr]) = (nodes[u].child, nodes[v].child);
+            let cnt = nodes[vl].val - nodes[ul].val;
+            if k < cnt {
+                u = ul;
+                v = vl;
+                r = m;
+            } else {
+                k -= cnt;
+                u = ur;
+                v = vr;
+                l = m;
+            }
+        }
+        Some(&self.sorted[l])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::math_traits::*;
     use super::*;
+    use rand::Rng;
 
     #[test]
     fn test_sum_segtree() {
@@ -113,4 +607,196 @@ mod tests {
             }
         }
     }
+
+    /// `x -> a * x + b`, applying the left operand first.
+    struct Affine;
+    impl Monoid for Affine {
+        type T = (u64, u64);
+        fn id() -> Self::T {
+            (1, 0)
+        }
+        fn op(f: &Self::T, g: &Self::T) -> Self::T {
+            (
+                f.0.wrapping_mul(g.0),
+                f.1.wrapping_mul(g.0).wrapping_add(g.1),
+            )
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_segtree() {
+        let mut rng = rand::thread_rng();
+        for n in 1..20 {
+            let mut v = (0..n)
+                .map(|_| (rng.gen_range(0, 100), rng.gen_range(0, 100)))
+                .collect::<Vec<(u64, u64)>>();
+            let mut st: SegTree<Affine> = v.clone().into();
+            for _ in 0..5 {
+                let i = rng.gen_range(0, n);
+                v[i] = (rng.gen_range(0, 100), rng.gen_range(0, 100));
+                st.set(i, v[i]);
+                let fold = |r: &[(u64, u64)]| r.iter().fold(Affine::id(), |a, b| Affine::op(&a, b));
+                for l in 0..=n {
+                    for r in l..=n {
+                        assert_eq!(st.query(l..r), fold(&v[l..r]));
+                    }
+                    assert_eq!(st.query(l..), fold(&v[l..]));
+                }
+                assert_eq!(st.query(..), fold(&v));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_query_out_of_range() {
+        let st: SegTree<Affine> = vec![(1, 1); 5].into();
+        st.query(..8);
+    }
+
+    #[test]
+    fn test_max_right_min_left() {
+        let mut rng = rand::thread_rng();
+        for n in 0..20 {
+            let v = (0..n).map(|_| rng.gen_range(0, 10)).collect::<Vec<u32>>();
+            let st: SegTree<Additive<_>> = v.clone().into();
+            for k in 0..30 {
+                for l in 0..=n {
+                    let expected = (l..=n)
+                        .take_while(|&r| v[l..r].iter().sum::<u32>() <= k)
+                        .last()
+                        .unwrap();
+                    assert_eq!(st.max_right(l, |s| s <= k), expected);
+                }
+                for r in 0..=n {
+                    let expected = (0..=r).find(|&l| v[l..r].iter().sum::<u32>() <= k).unwrap();
+                    assert_eq!(st.min_left(r, |s| s <= k), expected);
+                }
+                let expected = (0..n).find(|&i| v[..=i].iter().sum::<u32>() >= k);
+                assert_eq!(st.kth(k), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_segtree() {
+        let mut rng = rand::thread_rng();
+        let (lo, hi) = (-20, 20);
+        let mut v = vec![0u32; (hi - lo) as usize];
+        let mut st = DynamicSegTree::<Additive<u32>>::new(lo..hi);
+        for _ in 0..50 {
+            let i = rng.gen_range(lo, hi);
+            let x = rng.gen_range(0, 10);
+            v[(i - lo) as usize] = x;
+            st.set(i, x);
+            assert_eq!(st.get(i), x);
+            let (l, r) = (rng.gen_range(lo, hi + 1), rng.gen_range(lo, hi + 1));
+            let (l, r) = (l.min(r), l.max(r));
+            let sum = |l: i64, r: i64| v[(l - lo) as usize..(r - lo) as usize].iter().sum::<u32>();
+            assert_eq!(st.query(l..r), sum(l, r));
+            assert_eq!(st.query(..), sum(lo, hi));
+            let k = rng.gen_range(0, 30);
+            let expected = (l..=hi).take_while(|&r| sum(l, r) <= k).last().unwrap();
+            assert_eq!(st.max_right(l, |s| s <= k), expected);
+        }
+
+        let big = 1_000_000_000_000_000_000;
+        let mut st = DynamicSegTree::<Additive<i64>>::new(-big..big);
+        st.set(-big, 1);
+        st.set(42, 2);
+        st.set(big - 1, 4);
+        assert_eq!(st.get(0), 0);
+        assert_eq!(st.query(..), 7);
+        assert_eq!(st.query(-big + 1..=42), 2);
+        assert_eq!(st.query(43..), 4);
+        assert_eq!(st.max_right(-big, |s| s < 3), 42);
+        assert_eq!(st.max_right(43, |s| s < 4), big - 1);
+        assert_eq!(st.max_right(43, |s| s < 5), big);
+
+        let mut st = DynamicSegTree::<Additive<i64>>::filled(-big..big, 1);
+        st.set(0, 0);
+        assert_eq!(st.get(1), 1);
+        assert_eq!(st.query(..), 2 * big - 1);
+        assert_eq!(st.query(-5..5), 9);
+        assert_eq!(st.max_right(-3, |s| s <= 4), 2);
+        assert_eq!(st.max_right(big - 3, |s| s <= 4), big);
+    }
+
+    #[test]
+    fn test_dynamic_segtree_near_max() {
+        let (lo, hi) = (9_000_000_000_000_000_000, 9_200_000_000_000_000_000);
+        let mut st = DynamicSegTree::<Additive<i64>>::new(lo..hi);
+        st.set(lo, 5);
+        st.set(hi - 1, 7);
+        assert_eq!(st.query(..), 12);
+        assert_eq!(st.query(lo + 1..=hi - 1), 7);
+        assert_eq!(st.get(hi - 1), 7);
+        assert_eq!(st.max_right(lo, |s| s < 12), hi - 1);
+        assert_eq!(st.max_right(lo, |s| s <= 12), hi);
+
+        let mut st = DynamicSegTree::<Additive<i64>>::filled(i64::MAX - 10..i64::MAX, 1);
+        st.set(i64::MAX - 1, 3);
+        assert_eq!(st.query(..), 12);
+        assert_eq!(st.query(i64::MAX - 2..=i64::MAX - 1), 4);
+        assert_eq!(st.max_right(i64::MAX - 5, |s| s < 7), i64::MAX - 1);
+        assert_eq!(st.max_right(i64::MAX - 5, |s| s <= 7), i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "width")]
+    fn test_dynamic_segtree_too_wide() {
+        DynamicSegTree::<Additive<i64>>::new(i64::MIN..i64::MAX);
+    }
+
+    #[test]
+    fn test_persistent_segtree() {
+        let mut rng = rand::thread_rng();
+        for n in 1..15 {
+            let v = (0..n)
+                .map(|_| (rng.gen_range(0, 100), rng.gen_range(0, 100)))
+                .collect::<Vec<(u64, u64)>>();
+            let mut st: PersistentSegTree<Affine> = v.clone().into();
+            let mut versions = vec![v];
+            for _ in 0..30 {
+                let base = rng.gen_range(0, versions.len());
+                let i = rng.gen_range(0, n);
+                let x = (rng.gen_range(0, 100), rng.gen_range(0, 100));
+                let mut v = versions[base].clone();
+                v[i] = x;
+                assert_eq!(st.set(base, i, x), versions.len());
+                versions.push(v);
+            }
+            assert_eq!(st.latest(), versions.len() - 1);
+            let fold = |r: &[(u64, u64)]| r.iter().fold(Affine::id(), |a, b| Affine::op(&a, b));
+            for (ver, v) in versions.iter().enumerate() {
+                for l in 0..=n {
+                    for r in l..=n {
+                        assert_eq!(st.query(ver, l..r), fold(&v[l..r]));
+                    }
+                }
+                assert_eq!(st.query(ver, ..), fold(v));
+                for (i, &x) in v.iter().enumerate() {
+                    assert_eq!(st.get(ver, i), x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_kth_smallest() {
+        let mut rng = rand::thread_rng();
+        for n in 0..20 {
+            let a = (0..n).map(|_| rng.gen_range(-5, 5)).collect::<Vec<i32>>();
+            let kth = RangeKthSmallest::new(&a);
+            for l in 0..=n {
+                for r in l..=n {
+                    let mut b = a[l..r].to_vec();
+                    b.sort_unstable();
+                    for k in 0..=b.len() {
+                        assert_eq!(kth.kth(l..r, k), b.get(k));
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,4 +1,15 @@
-use std::cmp::min;
+extern crate __procon_modint as modint;
+extern crate __procon_ntt as ntt;
+
+use modint::ModInt998244353;
+use ntt::Ntt998244353;
+use std::{
+    cmp::min,
+    collections::{hash_map::RandomState, BTreeMap, BTreeSet},
+    hash::{BuildHasher, Hasher},
+    iter::FromIterator,
+    ops::{Bound, Range, RangeBounds},
+};
 
 pub fn edit_distance<T: Eq>(s: &[T], t: &[T]) -> usize {
     let (n, m) = (s.len(), t.len());
@@ -23,8 +34,244 @@ pub fn edit_distance<T: Eq>(s: &[T], t: &[T]) -> usize {
     dp[n][m]
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    Keep,
+    Insert,
+    Delete,
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditCosts {
+    pub insert: usize,
+    pub delete: usize,
+    pub replace: usize,
+}
+
+impl Default for EditCosts {
+    fn default() -> Self {
+        Self {
+            insert: 1,
+            delete: 1,
+            replace: 1,
+        }
+    }
+}
+
+impl EditCosts {
+    fn of(&self, op: EditOp) -> usize {
+        match op {
+            EditOp::Keep => 0,
+            EditOp::Insert => self.insert,
+            EditOp::Delete => self.delete,
+            EditOp::Replace => self.replace,
+        }
+    }
+}
+
+fn edit_table<T: Eq>(s: &[T], t: &[T], costs: &EditCosts) -> Vec<Vec<usize>> {
+    let (n, m) = (s.len(), t.len());
+    let mut dp = vec![vec![0; m + 1]; n + 1];
+    for i in 0..=n {
+        for j in 0..=m {
+            dp[i][j] = match (i, j) {
+                (0, 0) => 0,
+                (0, _) => dp[0][j - 1] + costs.insert,
+                (_, 0) => dp[i - 1][0] + costs.delete,
+                _ => {
+                    let sub = if s[i - 1] == t[j - 1] {
+                        0
+                    } else {
+                        costs.replace
+                    };
+                    min(
+                        dp[i - 1][j - 1] + sub,
+                        min(dp[i - 1][j] + costs.delete, dp[i][j - 1] + costs.insert),
+                    )
+                }
+            };
+        }
+    }
+    dp
+}
+
+/// Minimum-cost script turning `s` into `t`, using a full `(n + 1) * (m + 1)` table.
+pub fn edit_script<T: Eq>(s: &[T], t: &[T], costs: &EditCosts) -> (usize, Vec<EditOp>) {
+    let dp = edit_table(s, t, costs);
+    let (mut i, mut j) = (s.len(), t.len());
+    let mut ops = vec![];
+    while i > 0 || j > 0 {
+        let op = if i > 0 && j > 0 && s[i - 1] == t[j - 1] && dp[i][j] == dp[i - 1][j - 1] {
+            EditOp::Keep
+        } else if i > 0 && j > 0 && dp[i][j] == dp[i - 1][j - 1] + costs.replace {
+            EditOp::Replace
+        } else if i > 0 && dp[i][j] == dp[i - 1][j] + costs.delete {
+            EditOp::Delete
+        } else {
+            EditOp::Insert
+        };
+        if op != EditOp::Insert {
+            i -= 1;
+        }
+        if op != EditOp::Delete {
+            j -= 1;
+        }
+        ops.push(op);
+    }
+    ops.reverse();
+    (dp[s.len()][t.len()], ops)
+}
+
+/// Last row of the edit table of `s` against `t`, or of their reversals if `rev`.
+fn edit_last_row<T: Eq>(s: &[T], t: &[T], costs: &EditCosts, rev: bool) -> Vec<usize> {
+    let (n, m) = (s.len(), t.len());
+    let mut row = (0..=m).map(|j| j * costs.insert).collect::<Vec<_>>();
+    for i in 1..=n {
+        let a = if rev { &s[n - i] } else { &s[i - 1] };
+        let mut diag = row[0];
+        row[0] += costs.delete;
+        for j in 1..=m {
+            let b = if rev { &t[m - j] } else { &t[j - 1] };
+            let sub = if a == b { 0 } else { costs.replace };
+            let next = min(
+                diag + sub,
+                min(row[j] + costs.delete, row[j - 1] + costs.insert),
+            );
+            diag = row[j];
+            row[j] = next;
+        }
+    }
+    row
+}
+
+fn hirschberg<T: Eq>(s: &[T], t: &[T], costs: &EditCosts, ops: &mut Vec<EditOp>) {
+    if s.len() <= 1 || t.is_empty() {
+        ops.extend(edit_script(s, t, costs).1);
+        return;
+    }
+    let (n, m) = (s.len(), t.len());
+    let mid = n / 2;
+    let f = edit_last_row(&s[..mid], t, costs, false);
+    let g = edit_last_row(&s[mid..], t, costs, true);
+    let k = (0..=m).min_by_key(|&k| f[k] + g[m - k]).unwrap();
+    hirschberg(&s[..mid], &t[..k], costs, ops);
+    hirschberg(&s[mid..], &t[k..], costs, ops);
+}
+
+/// Same as `edit_script` but in O(n + m) memory by Hirschberg's algorithm.
+pub fn edit_script_hirschberg<T: Eq>(s: &[T], t: &[T], costs: &EditCosts) -> (usize, Vec<EditOp>) {
+    let mut ops = vec![];
+    hirschberg(s, t, costs, &mut ops);
+    (ops.iter().map(|&op| costs.of(op)).sum(), ops)
+}
+
+/// Unit-cost edit distance by Myers' bit-parallel algorithm in O(n * m / 64).
+pub fn edit_distance_bit_parallel<T: Ord>(s: &[T], t: &[T]) -> usize {
+    let (n, m) = (s.len(), t.len());
+    if n == 0 {
+        return m;
+    }
+    let w = (n - 1) / 64 + 1;
+    let mut peq = BTreeMap::new();
+    for (i, c) in s.iter().enumerate() {
+        peq.entry(c).or_insert_with(|| vec![0u64; w])[i / 64] |= 1 << (i % 64);
+    }
+    let zero = vec![0u64; w];
+    let top = 1u64 << ((n - 1) % 64);
+    let mut pv = vec![!0u64; w];
+    let mut mv = vec![0u64; w];
+    let mut score = n;
+    for c in t {
+        let eq = peq.get(c).unwrap_or(&zero);
+        let (mut add_carry, mut ph_carry, mut mh_carry) = (0, 1, 0);
+        for k in 0..w {
+            let xv = eq[k] | mv[k];
+            let (sum, c1) = (eq[k] & pv[k]).overflowing_add(pv[k]);
+            let (sum, c2) = sum.overflowing_add(add_carry);
+            add_carry = (c1 || c2) as u64;
+            let xh = (sum ^ pv[k]) | eq[k];
+            let mut ph = mv[k] | !(xh | pv[k]);
+            let mut mh = pv[k] & xh;
+            if k == w - 1 {
+                if ph & top != 0 {
+                    score += 1;
+                } else if mh & top != 0 {
+                    score -= 1;
+                }
+            }
+            let (ph_out, mh_out) = (ph >> 63, mh >> 63);
+            ph = (ph << 1) | ph_carry;
+            mh = (mh << 1) | mh_carry;
+            ph_carry = ph_out;
+            mh_carry = mh_out;
+            pv[k] = mh | !(xv | ph);
+            mv[k] = ph & xv;
+        }
+    }
+    score
+}
+
+/// Longest common subsequence of `s` and `t`, as its length and one witness.
+pub fn lcs<T: Eq + Clone>(s: &[T], t: &[T]) -> (usize, Vec<T>) {
+    let costs = EditCosts {
+        insert: 1,
+        delete: 1,
+        replace: 2,
+    };
+    let (_, ops) = edit_script_hirschberg(s, t, &costs);
+    let mut i = 0;
+    let mut res = vec![];
+    for op in ops {
+        match op {
+            EditOp::Keep => {
+                res.push(s[i].clone());
+                i += 1;
+            }
+            EditOp::Delete | EditOp::Replace => i += 1,
+            EditOp::Insert => {}
+        }
+    }
+    (res.len(), res)
+}
+
+/// Length of the longest common subsequence by bit-parallel DP in O(n * m / 64).
+pub fn lcs_length_bit_parallel<T: Ord>(s: &[T], t: &[T]) -> usize {
+    let n = s.len();
+    if n == 0 {
+        return 0;
+    }
+    let w = (n - 1) / 64 + 1;
+    let mut masks = BTreeMap::new();
+    for (i, c) in s.iter().enumerate() {
+        masks.entry(c).or_insert_with(|| vec![0u64; w])[i / 64] |= 1 << (i % 64);
+    }
+    let mut v = vec![!0u64; w];
+    for c in t {
+        let mask = match masks.get(c) {
+            Some(mask) => mask,
+            None => continue,
+        };
+        let (mut carry, mut borrow) = (false, false);
+        for k in 0..w {
+            let u = v[k] & mask[k];
+            let (sum, c1) = v[k].overflowing_add(u);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            let (diff, b1) = v[k].overflowing_sub(u);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            carry = c1 || c2;
+            borrow = b1 || b2;
+            v[k] = sum | diff;
+        }
+    }
+    (0..n).filter(|&i| v[i / 64] >> (i % 64) & 1 == 0).count()
+}
+
 pub fn z_algorithm<B: AsRef<[u8]>>(s: B) -> Vec<usize> {
-    let s = s.as_ref();
+    z_array(s.as_ref())
+}
+
+fn z_array<T: Eq>(s: &[T]) -> Vec<usize> {
     let mut z = vec![0; s.len()];
     let mut j = 0;
     for i in 1..s.len() {
@@ -43,9 +290,871 @@ pub fn z_algorithm<B: AsRef<[u8]>>(s: B) -> Vec<usize> {
     z
 }
 
+/// Lyndon factorization of `s` by Duval's algorithm.
+pub fn lyndon_factorization<T: Ord>(s: &[T]) -> Vec<Range<usize>> {
+    let n = s.len();
+    let mut res = vec![];
+    let mut i = 0;
+    while i < n {
+        let (mut j, mut k) = (i + 1, i);
+        while j < n && s[k] <= s[j] {
+            k = if s[k] < s[j] { i } else { k + 1 };
+            j += 1;
+        }
+        while i <= k {
+            res.push(i..i + j - k);
+            i += j - k;
+        }
+    }
+    res
+}
+
+/// Smallest `k` such that `s[k..] + s[..k]` is the lexicographically minimal rotation.
+pub fn minimum_rotation<T: Ord>(s: &[T]) -> usize {
+    let n = s.len();
+    let (mut i, mut res) = (0, 0);
+    while i < n {
+        res = i;
+        let (mut j, mut k) = (i + 1, i);
+        while j < 2 * n && s[k % n] <= s[j % n] {
+            k = if s[k % n] < s[j % n] { i } else { k + 1 };
+            j += 1;
+        }
+        while i <= k {
+            i += j - k;
+        }
+    }
+    res
+}
+
+/// Runs crossing the boundary between `l` and `r`, as `(p, a, b)` meaning the
+/// interval extends `a` to the left and `b` to the right with period `p <= l.len()`.
+fn runs_crossing<T: Eq + Clone>(l: &[T], r: &[T]) -> Vec<(usize, usize, usize)> {
+    let (n, m) = (l.len(), r.len());
+    let rev = l.iter().rev().cloned().collect::<Vec<_>>();
+    let mut t = r.to_vec();
+    t.extend_from_slice(l);
+    t.extend_from_slice(r);
+    let (zs, zt) = (z_array(&rev), z_array(&t));
+    let mut res = vec![];
+    for p in 1..=n {
+        let a = if p == n { p } else { min(zs[p] + p, n) };
+        let b = min(zt[n + m - p], m);
+        if a + b >= 2 * p {
+            res.push((p, a, b));
+        }
+    }
+    res
+}
+
+fn runs_dfs<T: Eq + Clone>(s: &[T], l: usize, r: usize, by_p: &mut Vec<Vec<(usize, usize)>>) {
+    if r - l <= 1 {
+        return;
+    }
+    let m = (l + r) / 2;
+    runs_dfs(s, l, m, by_p);
+    runs_dfs(s, m, r, by_p);
+    for (p, a, b) in runs_crossing(&s[l..m], &s[m..r]) {
+        by_p[p].push((m - a, m + b));
+    }
+    let rl = s[l..m].iter().rev().cloned().collect::<Vec<_>>();
+    let rr = s[m..r].iter().rev().cloned().collect::<Vec<_>>();
+    for (p, a, b) in runs_crossing(&rr, &rl) {
+        by_p[p].push((m - b, m + a));
+    }
+}
+
+/// All runs (maximal repetitions) of `s` as `(period, l, r)`, meaning `s[l..r]` has
+/// minimal period `period` and `r - l >= 2 * period`. Sorted by period, then by `l`.
+pub fn runs<T: Eq + Clone>(s: &[T]) -> Vec<(usize, usize, usize)> {
+    let n = s.len();
+    let mut by_p = vec![vec![]; n + 1];
+    runs_dfs(s, 0, n, &mut by_p);
+    let mut done = BTreeSet::new();
+    let mut res = vec![];
+    for (p, intervals) in by_p.iter_mut().enumerate() {
+        intervals.sort_by(|x, y| x.0.cmp(&y.0).then(y.1.cmp(&x.1)));
+        let mut last = 0;
+        for &(l, r) in intervals.iter() {
+            if r <= last {
+                continue;
+            }
+            last = r;
+            if done.insert((l, r)) {
+                res.push((p, l, r));
+            }
+        }
+    }
+    res
+}
+
+pub fn prefix_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut pi = vec![0; s.len()];
+    for i in 1..s.len() {
+        let mut j = pi[i - 1];
+        while j > 0 && s[i] != s[j] {
+            j = pi[j - 1];
+        }
+        if s[i] == s[j] {
+            j += 1;
+        }
+        pi[i] = j;
+    }
+    pi
+}
+
+/// Smallest `p > 0` such that `s[i] == s[i + p]` for every valid `i`.
+/// `s` is a repetition of its prefix of length `p` iff `p` divides `s.len()`.
+pub fn minimal_period<T: Eq>(s: &[T]) -> usize {
+    match prefix_function(s).last() {
+        Some(&b) => s.len() - b,
+        None => 0,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KmpMatcher<T> {
+    pattern: Vec<T>,
+    fail: Vec<usize>,
+    state: usize,
+    pos: usize,
+}
+
+impl<T: Eq + Clone> KmpMatcher<T> {
+    pub fn new(pattern: &[T]) -> Self {
+        assert!(!pattern.is_empty());
+        Self {
+            pattern: pattern.to_vec(),
+            fail: prefix_function(pattern),
+            state: 0,
+            pos: 0,
+        }
+    }
+
+    /// Number of pattern characters currently matched, in `0..=pattern.len()`.
+    pub fn state(&self) -> usize {
+        self.state
+    }
+
+    /// Transition of the KMP automaton, usable as the state space of a DP.
+    pub fn next_state(&self, mut state: usize, c: &T) -> usize {
+        if state == self.pattern.len() {
+            state = self.fail[state - 1];
+        }
+        while state > 0 && self.pattern[state] != *c {
+            state = self.fail[state - 1];
+        }
+        if self.pattern[state] == *c {
+            state + 1
+        } else {
+            0
+        }
+    }
+
+    /// Feeds one character and returns the start of the occurrence ending at it, if any.
+    pub fn push(&mut self, c: &T) -> Option<usize> {
+        self.state = self.next_state(self.state, c);
+        self.pos += 1;
+        if self.state == self.pattern.len() {
+            Some(self.pos - self.pattern.len())
+        } else {
+            None
+        }
+    }
+
+    /// Feeds a chunk of text and returns the starts of the occurrences ending inside it.
+    pub fn feed(&mut self, text: &[T]) -> Vec<usize> {
+        text.iter().filter_map(|c| self.push(c)).collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.state = 0;
+        self.pos = 0;
+    }
+}
+
+/// Returns `(odd, even)` where `s[i + 1 - odd[i]..i + odd[i]]` and `s[i - even[i]..i + even[i]]`
+/// are the longest palindromes centered at `i` and just before `i` respectively.
+pub fn manacher<T: Eq>(s: &[T]) -> (Vec<usize>, Vec<usize>) {
+    let n = s.len();
+    let mut odd = vec![0; n];
+    let (mut l, mut r) = (0, 0);
+    for i in 0..n {
+        let mut k = if i < r {
+            min(odd[l + r - 1 - i], r - i)
+        } else {
+            1
+        };
+        while i + k < n && k <= i && s[i - k] == s[i + k] {
+            k += 1;
+        }
+        odd[i] = k;
+        if i + k > r {
+            l = i + 1 - k;
+            r = i + k;
+        }
+    }
+    let mut even = vec![0; n];
+    let (mut l, mut r) = (0, 0);
+    for i in 0..n {
+        let mut k = if i < r {
+            min(even[l + r - i], r - i)
+        } else {
+            0
+        };
+        while i + k < n && k < i && s[i - k - 1] == s[i + k] {
+            k += 1;
+        }
+        even[i] = k;
+        if i + k > r {
+            l = i - k;
+            r = i + k;
+        }
+    }
+    (odd, even)
+}
+
+#[derive(Debug, Clone)]
+pub struct Palindromes {
+    odd: Vec<usize>,
+    even: Vec<usize>,
+}
+
+impl Palindromes {
+    pub fn new<T: Eq>(s: &[T]) -> Self {
+        let (odd, even) = manacher(s);
+        Self { odd, even }
+    }
+
+    pub fn is_palindrome<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        use Bound::*;
+        let l = match range.start_bound() {
+            Unbounded => 0,
+            Included(&i) => i,
+            Excluded(&i) => i + 1,
+        };
+        let r = match range.end_bound() {
+            Unbounded => self.odd.len(),
+            Included(&i) => i + 1,
+            Excluded(&i) => i,
+        };
+        assert!(l <= r && r <= self.odd.len());
+        let len = r - l;
+        if len == 0 {
+            true
+        } else if len % 2 == 1 {
+            self.odd[l + len / 2] > len / 2
+        } else {
+            self.even[l + len / 2] >= len / 2
+        }
+    }
+
+    /// Range of the leftmost longest palindromic substring.
+    pub fn longest(&self) -> Range<usize> {
+        let mut res = 0..0;
+        for i in 0..self.odd.len() {
+            if 2 * self.odd[i] - 1 > res.len() {
+                res = i + 1 - self.odd[i]..i + self.odd[i];
+            }
+            if 2 * self.even[i] > res.len() {
+                res = i - self.even[i]..i + self.even[i];
+            }
+        }
+        res
+    }
+
+    /// Number of non-empty palindromic substrings, counted by position.
+    pub fn count(&self) -> usize {
+        self.odd.iter().sum::<usize>() + self.even.iter().sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SuffixAutomatonState<T> {
+    pub len: usize,
+    pub link: Option<usize>,
+    pub next: BTreeMap<T, usize>,
+    is_clone: bool,
+}
+
+impl<T> SuffixAutomatonState<T> {
+    fn new(len: usize, link: Option<usize>) -> Self {
+        Self {
+            len,
+            link,
+            next: BTreeMap::new(),
+            is_clone: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SuffixAutomaton<T> {
+    states: Vec<SuffixAutomatonState<T>>,
+    last: usize,
+    distinct: usize,
+    occ: Vec<usize>,
+    paths: Vec<usize>,
+}
+
+impl<T: Ord + Clone> Default for SuffixAutomaton<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for SuffixAutomaton<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut sam = Self::new();
+        iter.into_iter().for_each(|c| sam.push(c));
+        sam
+    }
+}
+
+impl<T: Ord + Clone> SuffixAutomaton<T> {
+    pub fn new() -> Self {
+        Self {
+            states: vec![SuffixAutomatonState::new(0, None)],
+            last: 0,
+            distinct: 0,
+            occ: vec![],
+            paths: vec![],
+        }
+    }
+
+    pub fn push(&mut self, c: T) {
+        let cur = self.states.len();
+        self.states.push(SuffixAutomatonState::new(
+            self.states[self.last].len + 1,
+            Some(0),
+        ));
+        let mut p = Some(self.last);
+        while let Some(v) = p {
+            if self.states[v].next.contains_key(&c) {
+                break;
+            }
+            self.states[v].next.insert(c.clone(), cur);
+            p = self.states[v].link;
+        }
+        if let Some(v) = p {
+            let q = self.states[v].next[&c];
+            if self.states[v].len + 1 == self.states[q].len {
+                self.states[cur].link = Some(q);
+            } else {
+                let clone = self.states.len();
+                let mut st = self.states[q].clone();
+                st.len = self.states[v].len + 1;
+                st.is_clone = true;
+                self.states.push(st);
+                let mut p = Some(v);
+                while let Some(v) = p {
+                    if self.states[v].next.get(&c) != Some(&q) {
+                        break;
+                    }
+                    self.states[v].next.insert(c.clone(), clone);
+                    p = self.states[v].link;
+                }
+                self.states[q].link = Some(clone);
+                self.states[cur].link = Some(clone);
+            }
+        }
+        let link = self.states[cur].link.unwrap();
+        self.distinct += self.states[cur].len - self.states[link].len;
+        self.last = cur;
+    }
+
+    pub fn states(&self) -> &[SuffixAutomatonState<T>] {
+        &self.states
+    }
+
+    /// State reached by reading `pattern` from the initial state.
+    pub fn walk(&self, pattern: &[T]) -> Option<usize> {
+        pattern
+            .iter()
+            .try_fold(0, |v, c| self.states[v].next.get(c).copied())
+    }
+
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        self.walk(pattern).is_some()
+    }
+
+    /// Precomputes endpos sizes and path counts for `count`, `endpos_sizes` and
+    /// `kth_substring`. Has to be called again after further `push`es.
+    pub fn build_counts(&mut self) {
+        let order = self.order_by_len();
+        self.occ = self
+            .states
+            .iter()
+            .map(|st| if st.is_clone { 0 } else { 1 })
+            .collect();
+        self.occ[0] = 0;
+        self.paths = vec![1; self.states.len()];
+        for &v in order.iter().rev() {
+            if let Some(link) = self.states[v].link {
+                self.occ[link] += self.occ[v];
+            }
+            self.paths[v] += self.states[v]
+                .next
+                .values()
+                .map(|&u| self.paths[u])
+                .sum::<usize>();
+        }
+    }
+
+    fn assert_counts_built(&self) {
+        assert_eq!(
+            self.occ.len(),
+            self.states.len(),
+            "build_counts must be called after the last push"
+        );
+    }
+
+    /// Number of (possibly overlapping) occurrences of `pattern`. Needs `build_counts`.
+    pub fn count(&self, pattern: &[T]) -> usize {
+        match self.walk(pattern) {
+            Some(v) => self.endpos_sizes()[v],
+            None => 0,
+        }
+    }
+
+    /// Size of the endpos set of each state. Needs `build_counts`.
+    pub fn endpos_sizes(&self) -> &[usize] {
+        self.assert_counts_built();
+        &self.occ
+    }
+
+    /// Number of distinct non-empty substrings.
+    pub fn distinct_substrings(&self) -> usize {
+        self.distinct
+    }
+
+    /// The `k`-th (0-indexed) distinct non-empty substring in lexicographic order.
+    /// Needs `build_counts`.
+    pub fn kth_substring(&self, mut k: usize) -> Option<Vec<T>> {
+        self.assert_counts_built();
+        let mut res = vec![];
+        let mut v = 0;
+        'outer: loop {
+            for (c, &u) in self.states[v].next.iter() {
+                if k < self.paths[u] {
+                    res.push(c.clone());
+                    if k == 0 {
+                        return Some(res);
+                    }
+                    k -= 1;
+                    v = u;
+                    continue 'outer;
+                }
+                k -= self.paths[u];
+            }
+            return None;
+        }
+    }
+
+    /// Range of `t` holding one of the longest substrings of `t` accepted by the automaton.
+    pub fn longest_common_substring(&self, t: &[T]) -> Range<usize> {
+        let (mut v, mut l) = (0, 0);
+        let (mut best, mut end) = (0, 0);
+        for (i, c) in t.iter().enumerate() {
+            while v != 0 && !self.states[v].next.contains_key(c) {
+                v = self.states[v].link.unwrap();
+                l = self.states[v].len;
+            }
+            if let Some(&u) = self.states[v].next.get(c) {
+                v = u;
+                l += 1;
+            }
+            if l > best {
+                best = l;
+                end = i + 1;
+            }
+        }
+        end - best..end
+    }
+
+    fn order_by_len(&self) -> Vec<usize> {
+        let mut buckets = vec![0; self.states[self.last].len + 2];
+        for st in &self.states {
+            buckets[st.len + 1] += 1;
+        }
+        for i in 1..buckets.len() {
+            buckets[i] += buckets[i - 1];
+        }
+        let mut order = vec![0; self.states.len()];
+        for (v, st) in self.states.iter().enumerate() {
+            order[buckets[st.len]] = v;
+            buckets[st.len] += 1;
+        }
+        order
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AhoCorasickBuilder<T> {
+    patterns: Vec<Vec<T>>,
+}
+
+impl<T: Ord + Clone> Default for AhoCorasickBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> AhoCorasickBuilder<T> {
+    pub fn new() -> Self {
+        Self { patterns: vec![] }
+    }
+
+    /// Registers a non-empty `pattern` and returns its id.
+    pub fn add(&mut self, pattern: &[T]) -> usize {
+        assert!(!pattern.is_empty());
+        self.patterns.push(pattern.to_vec());
+        self.patterns.len() - 1
+    }
+
+    pub fn build(&self) -> AhoCorasick<T> {
+        let mut goto = vec![BTreeMap::new()];
+        let mut outputs = vec![vec![]];
+        let mut lens = vec![];
+        for (id, pattern) in self.patterns.iter().enumerate() {
+            let mut v = 0;
+            for c in pattern {
+                v = match goto[v].get(c) {
+                    Some(&u) => u,
+                    None => {
+                        goto.push(BTreeMap::new());
+                        outputs.push(vec![]);
+                        let u = goto.len() - 1;
+                        goto[v].insert(c.clone(), u);
+                        u
+                    }
+                };
+            }
+            outputs[v].push(id);
+            lens.push(pattern.len());
+        }
+        let n = goto.len();
+        let mut ac = AhoCorasick {
+            goto,
+            fail: vec![0; n],
+            dict: vec![None; n],
+            terminal: vec![false; n],
+            outputs,
+            lens,
+            order: vec![0],
+        };
+        let mut head = 0;
+        while head < ac.order.len() {
+            let v = ac.order[head];
+            head += 1;
+            for (c, &u) in ac.goto[v].iter() {
+                let f = if v == 0 {
+                    0
+                } else {
+                    ac.next_state(ac.fail[v], c)
+                };
+                ac.fail[u] = f;
+                ac.dict[u] = if ac.outputs[f].is_empty() {
+                    ac.dict[f]
+                } else {
+                    Some(f)
+                };
+                ac.terminal[u] = !ac.outputs[u].is_empty() || ac.terminal[f];
+                ac.order.push(u);
+            }
+        }
+        ac
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<T> {
+    goto: Vec<BTreeMap<T, usize>>,
+    fail: Vec<usize>,
+    dict: Vec<Option<usize>>,
+    terminal: Vec<bool>,
+    outputs: Vec<Vec<usize>>,
+    lens: Vec<usize>,
+    order: Vec<usize>,
+}
+
+impl<T: Ord + Clone> AhoCorasick<T> {
+    /// Number of states. The initial state is `0`.
+    pub fn num_states(&self) -> usize {
+        self.goto.len()
+    }
+
+    pub fn fail(&self, state: usize) -> usize {
+        self.fail[state]
+    }
+
+    /// Whether some pattern is a suffix of the string spelled by `state`.
+    pub fn is_terminal(&self, state: usize) -> bool {
+        self.terminal[state]
+    }
+
+    /// Ids of every pattern that is a suffix of the string spelled by `state`.
+    pub fn matches(&self, state: usize) -> Vec<usize> {
+        let mut res = self.outputs[state].clone();
+        let mut v = self.dict[state];
+        while let Some(u) = v {
+            res.extend_from_slice(&self.outputs[u]);
+            v = self.dict[u];
+        }
+        res
+    }
+
+    pub fn next_state(&self, mut state: usize, c: &T) -> usize {
+        loop {
+            if let Some(&u) = self.goto[state].get(c) {
+                return u;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.fail[state];
+        }
+    }
+
+    /// Dense transition table where `table[state][k]` is the state after reading `alphabet[k]`.
+    pub fn transition_table(&self, alphabet: &[T]) -> Vec<Vec<usize>> {
+        let mut table = vec![vec![0; alphabet.len()]; self.num_states()];
+        for &v in &self.order {
+            for (k, c) in alphabet.iter().enumerate() {
+                table[v][k] = match self.goto[v].get(c) {
+                    Some(&u) => u,
+                    None if v == 0 => 0,
+                    None => table[self.fail[v]][k],
+                };
+            }
+        }
+        table
+    }
+
+    /// Every occurrence as `(pattern id, end)`, where the match is `text[end - len..end]`.
+    pub fn find_all(&self, text: &[T]) -> Vec<(usize, usize)> {
+        let mut res = vec![];
+        let mut v = 0;
+        for (i, c) in text.iter().enumerate() {
+            v = self.next_state(v, c);
+            if self.terminal[v] {
+                res.extend(self.matches(v).into_iter().map(|id| (id, i + 1)));
+            }
+        }
+        res
+    }
+
+    pub fn pattern_len(&self, id: usize) -> usize {
+        self.lens[id]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EertreeNode<T> {
+    pub len: usize,
+    pub link: usize,
+    pub series_link: usize,
+    pub diff: usize,
+    /// Number of prefixes whose longest palindromic suffix is this node.
+    pub count: usize,
+    pub next: BTreeMap<T, usize>,
+    end: usize,
+}
+
+impl<T> EertreeNode<T> {
+    fn new(len: usize, link: usize, end: usize) -> Self {
+        Self {
+            len,
+            link,
+            series_link: 1,
+            diff: 0,
+            count: 0,
+            next: BTreeMap::new(),
+            end,
+        }
+    }
+}
+
+/// Palindromic tree. Node `0` is the root of length -1 and node `1` the root of length 0.
+#[derive(Debug, Clone)]
+pub struct Eertree<T> {
+    s: Vec<T>,
+    nodes: Vec<EertreeNode<T>>,
+    last: usize,
+}
+
+impl<T: Ord + Clone> Default for Eertree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for Eertree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        iter.into_iter().for_each(|c| {
+            tree.push(c);
+        });
+        tree
+    }
+}
+
+impl<T: Ord + Clone> Eertree<T> {
+    pub fn new() -> Self {
+        Self {
+            s: vec![],
+            nodes: vec![EertreeNode::new(0, 0, 0), EertreeNode::new(0, 0, 0)],
+            last: 1,
+        }
+    }
+
+    fn find(&self, mut v: usize, i: usize) -> usize {
+        while v != 0 {
+            let l = self.nodes[v].len;
+            if i > l && self.s[i - l - 1] == self.s[i] {
+                break;
+            }
+            v = self.nodes[v].link;
+        }
+        v
+    }
+
+    /// Appends `c` and returns the node of the longest palindromic suffix.
+    pub fn push(&mut self, c: T) -> usize {
+        let i = self.s.len();
+        self.s.push(c.clone());
+        let v = self.find(self.last, i);
+        if let Some(&u) = self.nodes[v].next.get(&c) {
+            self.last = u;
+        } else {
+            let len = if v == 0 { 1 } else { self.nodes[v].len + 2 };
+            let link = if len == 1 {
+                1
+            } else {
+                let w = self.find(self.nodes[v].link, i);
+                self.nodes[w].next[&c]
+            };
+            let mut node = EertreeNode::new(len, link, i + 1);
+            node.diff = len - self.nodes[link].len;
+            node.series_link = if node.diff == self.nodes[link].diff {
+                self.nodes[link].series_link
+            } else {
+                link
+            };
+            self.nodes.push(node);
+            self.last = self.nodes.len() - 1;
+            self.nodes[v].next.insert(c, self.last);
+        }
+        self.nodes[self.last].count += 1;
+        self.last
+    }
+
+    pub fn nodes(&self) -> &[EertreeNode<T>] {
+        &self.nodes
+    }
+
+    /// Node of the longest palindromic suffix of the current string.
+    pub fn last(&self) -> usize {
+        self.last
+    }
+
+    /// Number of distinct non-empty palindromic substrings.
+    pub fn distinct_palindromes(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// Range of the first occurrence of the palindrome of each non-root node, in creation order.
+    pub fn palindromes(&self) -> Vec<Range<usize>> {
+        self.nodes[2..]
+            .iter()
+            .map(|node| node.end - node.len..node.end)
+            .collect()
+    }
+
+    /// Total number of occurrences of each node's palindrome.
+    pub fn occurrences(&self) -> Vec<usize> {
+        let mut occ = self.nodes.iter().map(|node| node.count).collect::<Vec<_>>();
+        for v in (2..self.nodes.len()).rev() {
+            occ[self.nodes[v].link] += occ[v];
+        }
+        occ
+    }
+}
+
+/// Minimum number of palindromes `s` can be split into, via series links in O(n log n).
+pub fn palindromic_factorization<T: Ord + Clone>(s: &[T]) -> usize {
+    let n = s.len();
+    let mut tree = Eertree::new();
+    let mut dp = vec![0; n + 1];
+    let mut series = vec![0; n + 2];
+    for i in 1..=n {
+        let mut v = tree.push(s[i - 1].clone());
+        series.resize(tree.nodes().len(), 0);
+        let nodes = tree.nodes();
+        dp[i] = usize::MAX;
+        while nodes[v].len > 0 {
+            let sl = nodes[v].series_link;
+            series[v] = dp[i - (nodes[sl].len + nodes[v].diff)];
+            if nodes[v].diff == nodes[nodes[v].link].diff {
+                series[v] = min(series[v], series[nodes[v].link]);
+            }
+            dp[i] = min(dp[i], series[v] + 1);
+            v = sl;
+        }
+    }
+    dp[n]
+}
+
+/// Starting offsets where `pattern` matches `text`, where `wildcard` in either of them
+/// matches any symbol. Uses Σ p·t·(p − t)² over NTT in O((n + m) log (n + m)) with symbols
+/// mapped to random nonzero elements of GF(998244353), so each offset is reported falsely
+/// with probability at most 4 / 998244353.
+pub fn wildcard_matching<T: Ord>(text: &[T], pattern: &[T], wildcard: &T) -> Vec<usize> {
+    let (n, m) = (text.len(), pattern.len());
+    if m == 0 {
+        return (0..=n).collect();
+    }
+    if m > n {
+        return vec![];
+    }
+    let mut symbols = text
+        .iter()
+        .chain(pattern.iter())
+        .filter(|&c| c != wildcard)
+        .collect::<Vec<_>>();
+    symbols.sort();
+    symbols.dedup();
+    let state = RandomState::new();
+    let codes = (0..symbols.len())
+        .map(|k| {
+            let mut h = state.build_hasher();
+            h.write_usize(k);
+            ModInt998244353::new((h.finish() % 998244352) as i64 + 1)
+        })
+        .collect::<Vec<_>>();
+    let encode = |c: &T| match symbols.binary_search(&c) {
+        Ok(k) => codes[k],
+        Err(_) => ModInt998244353::new(0),
+    };
+    let t = text.iter().map(encode).collect::<Vec<_>>();
+    let p = pattern.iter().rev().map(encode).collect::<Vec<_>>();
+    let powers = |v: &[ModInt998244353], e: i64| v.iter().map(|x| x.pow(e)).collect::<Vec<_>>();
+    let a = Ntt998244353::multiply(&powers(&p, 3), &t);
+    let b = Ntt998244353::multiply(&powers(&p, 2), &powers(&t, 2));
+    let c = Ntt998244353::multiply(&p, &powers(&t, 3));
+    (0..=n - m)
+        .filter(|&i| {
+            let k = i + m - 1;
+            (a[k] - b[k] * 2 + c[k]).value() == 0
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
 
     #[test]
     fn test_edit_distance_string() {
@@ -70,4 +1179,494 @@ mod tests {
         let z = z_algorithm(&s);
         assert_eq!(z, vec![5, 0, 3, 0, 1]);
     }
+
+    #[test]
+    fn test_suffix_automaton() {
+        let mut sam = "abcbc".bytes().collect::<SuffixAutomaton<_>>();
+        assert!(sam.contains(b"bcb"));
+        assert!(!sam.contains(b"cc"));
+        sam.build_counts();
+        let sam = &sam;
+        assert_eq!(sam.count(b"bc"), 2);
+        assert_eq!(sam.count(b"abcbc"), 1);
+        assert_eq!(sam.count(b"ca"), 0);
+        assert_eq!(sam.distinct_substrings(), 12);
+        assert_eq!(sam.kth_substring(0), Some(b"a".to_vec()));
+        assert_eq!(sam.kth_substring(5), Some(b"b".to_vec()));
+        assert_eq!(sam.kth_substring(12), None);
+        assert_eq!(sam.longest_common_substring(b"xxcbcbx"), 2..5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_suffix_automaton_stale_counts() {
+        let mut sam = "abc".bytes().collect::<SuffixAutomaton<_>>();
+        sam.build_counts();
+        sam.push(b'a');
+        sam.count(b"a");
+    }
+
+    #[test]
+    fn test_suffix_automaton_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(1..40));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'c')))
+                .collect::<Vec<_>>();
+            let mut sam = SuffixAutomaton::new();
+            let mut subs = BTreeSet::new();
+            for (i, &c) in s.iter().enumerate() {
+                sam.push(c);
+                (0..=i).for_each(|l| {
+                    subs.insert(s[l..=i].to_vec());
+                });
+                assert_eq!(sam.distinct_substrings(), subs.len());
+            }
+            sam.build_counts();
+            for (k, t) in subs.iter().enumerate() {
+                let occ = (0..=n - t.len()).filter(|&i| s[i..].starts_with(t));
+                assert_eq!(sam.count(t), occ.count());
+                assert_eq!(sam.kth_substring(k).as_ref(), Some(t));
+            }
+            let m = rng.sample(Uniform::from(1..40));
+            let t = (0..m)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'c')))
+                .collect::<Vec<_>>();
+            let r = sam.longest_common_substring(&t);
+            assert!(sam.contains(&t[r.clone()]));
+            let best = (0..m)
+                .flat_map(|i| (i..=m).map(move |j| (i, j)))
+                .filter(|&(i, j)| subs.contains(&t[i..j]))
+                .map(|(i, j)| j - i)
+                .max()
+                .unwrap_or(0);
+            assert_eq!(r.len(), best);
+        }
+    }
+
+    #[test]
+    fn test_prefix_function() {
+        assert_eq!(prefix_function(b"abacaba"), vec![0, 0, 1, 0, 1, 2, 3]);
+        assert_eq!(prefix_function(&[1, 1, 1, 2]), vec![0, 1, 2, 0]);
+        assert_eq!(minimal_period(b"abcabcab"), 3);
+        assert_eq!(minimal_period(b"abab"), 2);
+        assert_eq!(minimal_period(b"abcd"), 4);
+        assert_eq!(minimal_period::<u8>(&[]), 0);
+    }
+
+    #[test]
+    fn test_kmp_matcher() {
+        let mut kmp = KmpMatcher::new(b"aba");
+        assert_eq!(kmp.feed(b"ababa"), vec![0, 2]);
+        assert_eq!(kmp.feed(b"bab"), vec![4]);
+        assert_eq!(kmp.push(&b'a'), Some(6));
+        kmp.reset();
+        assert_eq!(kmp.state(), 0);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(1..200));
+            let m = rng.sample(Uniform::from(1..5));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(0..2)))
+                .collect::<Vec<_>>();
+            let t = (0..m)
+                .map(|_| rng.sample(Uniform::from(0..2)))
+                .collect::<Vec<_>>();
+            let expected = (0..n).filter(|&i| s[i..].starts_with(&t));
+            let mut kmp = KmpMatcher::new(&t);
+            let (a, b) = s.split_at(n / 2);
+            let mut found = kmp.feed(a);
+            found.extend(kmp.feed(b));
+            assert_eq!(found, expected.collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_manacher() {
+        let (odd, even) = manacher(b"abaaba");
+        assert_eq!(odd, vec![1, 2, 1, 1, 2, 1]);
+        assert_eq!(even, vec![0, 0, 0, 3, 0, 0]);
+        let p = Palindromes::new(b"abaaba");
+        assert_eq!(p.longest(), 0..6);
+        assert_eq!(p.count(), 11);
+        assert!(!p.is_palindrome(1..3));
+        assert!(p.is_palindrome(2..=3));
+        assert!(p.is_palindrome(..));
+        assert!(p.is_palindrome(4..4));
+    }
+
+    #[test]
+    fn test_palindromes_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(0..40));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'b')))
+                .collect::<Vec<_>>();
+            let p = Palindromes::new(&s);
+            let is_pal = |l: usize, r: usize| s[l..r].iter().eq(s[l..r].iter().rev());
+            let mut count = 0;
+            let mut longest = 0;
+            for l in 0..=n {
+                for r in l..=n {
+                    assert_eq!(p.is_palindrome(l..r), is_pal(l, r));
+                    if l < r && is_pal(l, r) {
+                        count += 1;
+                        longest = longest.max(r - l);
+                    }
+                }
+            }
+            assert_eq!(p.count(), count);
+            let r = p.longest();
+            assert_eq!(r.len(), longest);
+            assert!(is_pal(r.start, r.end));
+        }
+    }
+
+    #[test]
+    fn test_aho_corasick() {
+        let mut builder = AhoCorasickBuilder::new();
+        for p in &["he", "she", "his", "hers"] {
+            builder.add(p.as_bytes());
+        }
+        let ac = builder.build();
+        let mut found = ac.find_all(b"ushers");
+        found.sort();
+        assert_eq!(found, vec![(0, 4), (1, 4), (3, 6)]);
+        assert_eq!(ac.pattern_len(3), 4);
+        assert_eq!(ac.num_states(), 10);
+
+        let table = ac.transition_table(b"ehirsu");
+        let mut v = 0;
+        for &c in b"ushe" {
+            let k = b"ehirsu".iter().position(|&a| a == c).unwrap();
+            assert_eq!(table[v][k], ac.next_state(v, &c));
+            v = table[v][k];
+        }
+        assert!(ac.is_terminal(v));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_aho_corasick_empty_pattern() {
+        AhoCorasickBuilder::new().add(b"");
+    }
+
+    #[test]
+    fn test_aho_corasick_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let mut builder = AhoCorasickBuilder::new();
+            let k = rng.sample(Uniform::from(1..8));
+            let patterns = (0..k)
+                .map(|_| {
+                    let m = rng.sample(Uniform::from(1..5));
+                    (0..m)
+                        .map(|_| rng.sample(Uniform::from(0..3)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            patterns.iter().for_each(|p| {
+                builder.add(p);
+            });
+            let ac = builder.build();
+            let n = rng.sample(Uniform::from(0..100));
+            let text = (0..n)
+                .map(|_| rng.sample(Uniform::from(0..3)))
+                .collect::<Vec<_>>();
+            let mut expected = vec![];
+            for (id, p) in patterns.iter().enumerate() {
+                for end in p.len()..=n {
+                    if text[end - p.len()..end] == p[..] {
+                        expected.push((id, end));
+                    }
+                }
+            }
+            expected.sort();
+            let mut found = ac.find_all(&text);
+            found.sort();
+            assert_eq!(found, expected);
+
+            let table = ac.transition_table(&[0, 1, 2]);
+            let mut v = 0;
+            for &c in &text {
+                let u = table[v][c];
+                assert_eq!(u, ac.next_state(v, &c));
+                v = u;
+            }
+        }
+    }
+
+    #[test]
+    fn test_eertree() {
+        let mut tree = Eertree::new();
+        for &c in b"eertree" {
+            tree.push(c);
+        }
+        let s = b"eertree";
+        let mut pals = tree
+            .palindromes()
+            .into_iter()
+            .map(|r| s[r].to_vec())
+            .collect::<Vec<_>>();
+        pals.sort();
+        let expected: Vec<&[u8]> = vec![b"e", b"ee", b"eertree", b"ertre", b"r", b"rtr", b"t"];
+        assert_eq!(pals, expected);
+        assert_eq!(tree.distinct_palindromes(), 7);
+        assert_eq!(tree.nodes()[tree.last()].len, 7);
+        let occ = tree.occurrences();
+        let e = tree.nodes()[0].next[&b'e'];
+        assert_eq!(occ[e], 4);
+    }
+
+    #[test]
+    fn test_eertree_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(1..40));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'b')))
+                .collect::<Vec<_>>();
+            let tree = s.iter().copied().collect::<Eertree<_>>();
+            let occ = tree.occurrences();
+            let mut distinct = BTreeSet::new();
+            for l in 0..n {
+                for r in l + 1..=n {
+                    if s[l..r].iter().eq(s[l..r].iter().rev()) {
+                        distinct.insert(&s[l..r]);
+                    }
+                }
+            }
+            assert_eq!(tree.distinct_palindromes(), distinct.len());
+            for (k, r) in tree.palindromes().into_iter().enumerate() {
+                let p = &s[r];
+                assert!(distinct.contains(p));
+                let cnt = (0..=n - p.len()).filter(|&i| s[i..].starts_with(p));
+                assert_eq!(occ[k + 2], cnt.count());
+            }
+
+            let mut dp = vec![usize::MAX; n + 1];
+            dp[0] = 0;
+            for r in 1..=n {
+                for l in 0..r {
+                    if s[l..r].iter().eq(s[l..r].iter().rev()) {
+                        dp[r] = min(dp[r], dp[l] + 1);
+                    }
+                }
+            }
+            assert_eq!(palindromic_factorization(&s), dp[n]);
+        }
+    }
+
+    fn apply_script<T: Clone>(s: &[T], t: &[T], ops: &[EditOp]) -> Vec<T> {
+        let (mut i, mut j) = (0, 0);
+        let mut res = vec![];
+        for &op in ops {
+            match op {
+                EditOp::Keep => {
+                    res.push(s[i].clone());
+                    i += 1;
+                    j += 1;
+                }
+                EditOp::Replace => {
+                    res.push(t[j].clone());
+                    i += 1;
+                    j += 1;
+                }
+                EditOp::Insert => {
+                    res.push(t[j].clone());
+                    j += 1;
+                }
+                EditOp::Delete => i += 1,
+            }
+        }
+        assert_eq!(i, s.len());
+        res
+    }
+
+    #[test]
+    fn test_edit_script() {
+        let (s, t) = (b"kitten", b"sitting");
+        let (d, ops) = edit_script(s, t, &EditCosts::default());
+        assert_eq!(d, 3);
+        use EditOp::*;
+        assert_eq!(ops, vec![Replace, Keep, Keep, Keep, Replace, Keep, Insert]);
+        let costs = EditCosts {
+            insert: 1,
+            delete: 1,
+            replace: 5,
+        };
+        assert_eq!(edit_script(s, t, &costs).0, 5);
+        assert_eq!(edit_script_hirschberg(s, t, &costs).0, 5);
+        assert_eq!(edit_distance_bit_parallel(s, t), 3);
+    }
+
+    #[test]
+    fn test_edit_script_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let gen = |rng: &mut rand::rngs::ThreadRng| {
+                let n = rng.sample(Uniform::from(0..150));
+                (0..n)
+                    .map(|_| rng.sample(Uniform::from(0..4)))
+                    .collect::<Vec<_>>()
+            };
+            let (s, t) = (gen(&mut rng), gen(&mut rng));
+            let d = edit_distance(&s, &t);
+            assert_eq!(edit_distance_bit_parallel(&s, &t), d);
+            let costs = EditCosts {
+                insert: rng.sample(Uniform::from(1..5)),
+                delete: rng.sample(Uniform::from(1..5)),
+                replace: rng.sample(Uniform::from(1..5)),
+            };
+            let (d1, ops1) = edit_script(&s, &t, &costs);
+            let (d2, ops2) = edit_script_hirschberg(&s, &t, &costs);
+            assert_eq!(d1, d2);
+            for ops in &[ops1, ops2] {
+                assert_eq!(apply_script(&s, &t, ops), t);
+                assert_eq!(ops.iter().map(|&op| costs.of(op)).sum::<usize>(), d1);
+            }
+        }
+    }
+
+    fn is_subsequence<T: Eq>(a: &[T], s: &[T]) -> bool {
+        let mut it = s.iter();
+        a.iter().all(|c| it.any(|d| d == c))
+    }
+
+    #[test]
+    fn test_lcs() {
+        let (len, w) = lcs(b"ABCBDAB", b"BDCABA");
+        assert_eq!(len, 4);
+        assert!(is_subsequence(&w, b"ABCBDAB") && is_subsequence(&w, b"BDCABA"));
+        assert_eq!(lcs_length_bit_parallel(b"ABCBDAB", b"BDCABA"), 4);
+        assert_eq!(lcs::<u8>(b"", b"abc"), (0, vec![]));
+    }
+
+    #[test]
+    fn test_lcs_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let gen = |rng: &mut rand::rngs::ThreadRng| {
+                let n = rng.sample(Uniform::from(0..150));
+                (0..n)
+                    .map(|_| rng.sample(Uniform::from(0..4)))
+                    .collect::<Vec<_>>()
+            };
+            let (s, t) = (gen(&mut rng), gen(&mut rng));
+            let mut dp = vec![vec![0; t.len() + 1]; s.len() + 1];
+            for i in 0..s.len() {
+                for j in 0..t.len() {
+                    dp[i + 1][j + 1] = if s[i] == t[j] {
+                        dp[i][j] + 1
+                    } else {
+                        dp[i][j + 1].max(dp[i + 1][j])
+                    };
+                }
+            }
+            let expected = dp[s.len()][t.len()];
+            let (len, w) = lcs(&s, &t);
+            assert_eq!(len, expected);
+            assert_eq!(w.len(), expected);
+            assert!(is_subsequence(&w, &s) && is_subsequence(&w, &t));
+            assert_eq!(lcs_length_bit_parallel(&s, &t), expected);
+        }
+    }
+
+    #[test]
+    fn test_lyndon_factorization() {
+        let s = b"abracadabra";
+        let f = lyndon_factorization(s);
+        assert_eq!(f, vec![0..7, 7..10, 10..11]);
+        assert_eq!(lyndon_factorization(b"aaa"), vec![0..1, 1..2, 2..3]);
+        assert_eq!(minimum_rotation(b"bcab"), 2);
+        assert_eq!(minimum_rotation(b"abab"), 0);
+        assert_eq!(minimum_rotation(b"baba"), 1);
+    }
+
+    #[test]
+    fn test_lyndon_random() {
+        let mut rng = rand::thread_rng();
+        let is_lyndon = |w: &[u8]| (1..w.len()).all(|k| w < &w[k..]);
+        for _ in 0..200 {
+            let n = rng.sample(Uniform::from(1..30));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'c')))
+                .collect::<Vec<_>>();
+            let f = lyndon_factorization(&s);
+            assert_eq!(f.first().unwrap().start, 0);
+            assert_eq!(f.last().unwrap().end, n);
+            for w in f.windows(2) {
+                assert_eq!(w[0].end, w[1].start);
+                assert!(s[w[0].clone()] >= s[w[1].clone()]);
+            }
+            assert!(f.iter().all(|r| is_lyndon(&s[r.clone()])));
+
+            let rot = |k: usize| [&s[k..], &s[..k]].concat();
+            let best = (0..n).min_by_key(|&k| rot(k)).unwrap();
+            assert_eq!(minimum_rotation(&s), best);
+        }
+    }
+
+    #[test]
+    fn test_runs() {
+        assert_eq!(
+            runs(b"mississippi"),
+            vec![(1, 2, 4), (1, 5, 7), (1, 8, 10), (3, 1, 8)]
+        );
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.sample(Uniform::from(0..40));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(0..2)))
+                .collect::<Vec<_>>();
+            let mut expected = vec![];
+            let mut seen = BTreeSet::new();
+            for p in 1..=n / 2 {
+                let mut l = 0;
+                while l + p <= n {
+                    let mut r = l;
+                    while r + p < n && s[r] == s[r + p] {
+                        r += 1;
+                    }
+                    if r - l >= p && seen.insert((l, r + p)) {
+                        expected.push((p, l, r + p));
+                    }
+                    l = r + 1;
+                }
+            }
+            assert_eq!(runs(&s), expected);
+        }
+    }
+
+    #[test]
+    fn test_wildcard_matching() {
+        assert_eq!(wildcard_matching(b"abcabcab", b"a?c", &b'?'), vec![0, 3]);
+        assert_eq!(wildcard_matching(b"ab?ab", b"bc", &b'?'), vec![1]);
+        assert_eq!(wildcard_matching(b"ab", b"abc", &b'?'), vec![]);
+        assert_eq!(wildcard_matching(b"abc", b"", &b'?'), vec![0, 1, 2, 3]);
+        assert_eq!(wildcard_matching(b"", b"", &b'?'), vec![0]);
+        // Sums to a multiple of 998244353 when symbols are coded by their rank.
+        let text = (1..=4000).chain(vec![3253, 301]).collect::<Vec<i64>>();
+        assert_eq!(wildcard_matching(&text, &[24, 24], &0), vec![]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(1..100));
+            let m = rng.sample(Uniform::from(1..=n));
+            let gen = |rng: &mut rand::rngs::ThreadRng, k: usize| {
+                (0..k)
+                    .map(|_| rng.sample(Uniform::from(0..4)))
+                    .collect::<Vec<_>>()
+            };
+            let (text, pattern) = (gen(&mut rng, n), gen(&mut rng, m));
+            let expected = (0..=n - m)
+                .filter(|&i| {
+                    (0..m).all(|j| text[i + j] == pattern[j] || text[i + j] == 0 || pattern[j] == 0)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(wildcard_matching(&text, &pattern, &0), expected);
+        }
+    }
 }
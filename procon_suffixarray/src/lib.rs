@@ -0,0 +1,485 @@
+extern crate __procon_sparse_table as sparse_table;
+
+use sparse_table::MinSparseTable;
+use std::{cmp::Ordering, ops::Range};
+
+const NONE: usize = usize::MAX;
+
+pub struct SuffixArray<T> {
+    s: Vec<T>,
+    sa: Vec<usize>,
+    rank: Vec<usize>,
+    lcp: MinSparseTable<usize>,
+}
+
+impl<B: AsRef<[u8]>> From<B> for SuffixArray<u8> {
+    fn from(s: B) -> SuffixArray<u8> {
+        let s = s.as_ref();
+        let codes = s.iter().map(|&c| c as usize).collect::<Vec<_>>();
+        Self::build(s.to_vec(), &codes, 255)
+    }
+}
+
+impl<T: Ord + Clone> SuffixArray<T> {
+    pub fn new(s: &[T]) -> Self {
+        let (codes, upper) = compress(s);
+        Self::build(s.to_vec(), &codes, upper)
+    }
+
+    /// Sorted starting positions of every occurrence of `pattern`.
+    pub fn find_all<P: AsRef<[T]>>(&self, pattern: P) -> Vec<usize> {
+        let mut res = self.sa[self.equal_range(pattern.as_ref())].to_vec();
+        res.sort_unstable();
+        res
+    }
+
+    pub fn count<P: AsRef<[T]>>(&self, pattern: P) -> usize {
+        self.equal_range(pattern.as_ref()).len()
+    }
+
+    pub fn contains<P: AsRef<[T]>>(&self, pattern: P) -> bool {
+        self.count(pattern) > 0
+    }
+
+    /// Range of `sa` whose suffixes start with `pattern`.
+    fn equal_range(&self, pattern: &[T]) -> Range<usize> {
+        let cmp = |p: usize| {
+            let t = &self.s[p..];
+            t[..t.len().min(pattern.len())].cmp(pattern)
+        };
+        let l = self.sa.partition_point(|&p| cmp(p) == Ordering::Less);
+        let r = self.sa.partition_point(|&p| cmp(p) != Ordering::Greater);
+        l..r
+    }
+
+    /// Returns the ranges of `a` and `b` that form one of their longest common substrings.
+    pub fn longest_common_substring(a: &[T], b: &[T]) -> (Range<usize>, Range<usize>) {
+        let joined = a.iter().chain(b.iter()).collect::<Vec<_>>();
+        let (codes, upper) = compress(&joined);
+        let sep = upper + 1;
+        let n = a.len();
+        let mut codes = codes;
+        codes.insert(n, sep);
+        let sa = sa_is(&codes, sep);
+        let mut rank = vec![0; sa.len()];
+        for (i, &p) in sa.iter().enumerate() {
+            rank[p] = i;
+        }
+        let lcp = kasai(&codes, &sa, &rank);
+        let (mut len, mut pa, mut pb) = (0, 0, 0);
+        for (i, &h) in lcp.iter().enumerate() {
+            let (x, y) = (sa[i], sa[i + 1]);
+            if h <= len || (x < n) == (y < n) {
+                continue;
+            }
+            let (x, y) = if x < n { (x, y) } else { (y, x) };
+            len = h;
+            pa = x;
+            pb = y - n - 1;
+        }
+        (pa..pa + len, pb..pb + len)
+    }
+}
+
+impl<T> SuffixArray<T> {
+    fn build(s: Vec<T>, codes: &[usize], upper: usize) -> Self {
+        let sa = sa_is(codes, upper);
+        let mut rank = vec![0; sa.len()];
+        for (i, &p) in sa.iter().enumerate() {
+            rank[p] = i;
+        }
+        let lcp = MinSparseTable::new(&kasai(codes, &sa, &rank));
+        Self { s, sa, rank, lcp }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sa.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sa.is_empty()
+    }
+
+    pub fn text(&self) -> &[T] {
+        &self.s
+    }
+
+    /// Starting positions of the suffixes in lexicographic order.
+    pub fn sa(&self) -> &[usize] {
+        &self.sa
+    }
+
+    /// Inverse of `sa`: `rank()[sa()[i]] == i`.
+    pub fn rank(&self) -> &[usize] {
+        &self.rank
+    }
+
+    /// `lcp_array()[i]` is the LCP of the suffixes `sa()[i]` and `sa()[i + 1]`.
+    pub fn lcp_array(&self) -> &[usize] {
+        &self.lcp.data
+    }
+
+    /// Length of the longest common prefix of the suffixes starting at `i` and `j`.
+    pub fn lcp(&self, i: usize, j: usize) -> usize {
+        if i == j {
+            return self.len() - i;
+        }
+        let (a, b) = if self.rank[i] < self.rank[j] {
+            (self.rank[i], self.rank[j])
+        } else {
+            (self.rank[j], self.rank[i])
+        };
+        self.lcp.data[self.lcp.query(a..b)]
+    }
+
+    /// Number of distinct non-empty substrings.
+    pub fn distinct_substrings(&self) -> usize {
+        let n = self.len();
+        n * (n + 1) / 2 - self.lcp.data.iter().sum::<usize>()
+    }
+
+    /// Range of the text holding one of its longest substrings that occur at least twice.
+    pub fn longest_repeated_substring(&self) -> Range<usize> {
+        match (0..self.lcp.data.len()).max_by_key(|&i| self.lcp.data[i]) {
+            Some(i) => self.sa[i]..self.sa[i] + self.lcp.data[i],
+            None => 0..0,
+        }
+    }
+}
+
+fn compress<T: Ord>(s: &[T]) -> (Vec<usize>, usize) {
+    let mut idx = (0..s.len()).collect::<Vec<_>>();
+    idx.sort_by(|&a, &b| s[a].cmp(&s[b]));
+    let mut codes = vec![0; s.len()];
+    let mut upper = 0;
+    for i in 1..idx.len() {
+        if s[idx[i - 1]] != s[idx[i]] {
+            upper += 1;
+        }
+        codes[idx[i]] = upper;
+    }
+    (codes, upper)
+}
+
+fn kasai(s: &[usize], sa: &[usize], rank: &[usize]) -> Vec<usize> {
+    let n = s.len();
+    let mut lcp = vec![0; n.saturating_sub(1)];
+    let mut h: usize = 0;
+    for i in 0..n {
+        h = h.saturating_sub(1);
+        if rank[i] == 0 {
+            continue;
+        }
+        let j = sa[rank[i] - 1];
+        while i + h < n && j + h < n && s[i + h] == s[j + h] {
+            h += 1;
+        }
+        lcp[rank[i] - 1] = h;
+    }
+    lcp
+}
+
+fn induce(
+    s: &[usize],
+    ls: &[bool],
+    sum_s: &[usize],
+    sum_l: &[usize],
+    lms: &[usize],
+    sa: &mut [usize],
+) {
+    let n = s.len();
+    sa.iter_mut().for_each(|v| *v = NONE);
+    let mut buf = sum_s.to_vec();
+    for &d in lms {
+        if d == n {
+            continue;
+        }
+        sa[buf[s[d]]] = d;
+        buf[s[d]] += 1;
+    }
+    buf.copy_from_slice(sum_l);
+    sa[buf[s[n - 1]]] = n - 1;
+    buf[s[n - 1]] += 1;
+    for i in 0..n {
+        let v = sa[i];
+        if v != NONE && v >= 1 && !ls[v - 1] {
+            sa[buf[s[v - 1]]] = v - 1;
+            buf[s[v - 1]] += 1;
+        }
+    }
+    buf.copy_from_slice(sum_l);
+    for i in (0..n).rev() {
+        let v = sa[i];
+        if v != NONE && v >= 1 && ls[v - 1] {
+            buf[s[v - 1] + 1] -= 1;
+            sa[buf[s[v - 1] + 1]] = v - 1;
+        }
+    }
+}
+
+/// SA-IS over `s` whose values lie in `0..=upper`.
+fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+    match n {
+        0 => return vec![],
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+    let mut ls = vec![false; n];
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] {
+            ls[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+    let mut sum_l = vec![0; upper + 2];
+    let mut sum_s = vec![0; upper + 2];
+    for i in 0..n {
+        if !ls[i] {
+            sum_s[s[i]] += 1;
+        } else {
+            sum_l[s[i] + 1] += 1;
+        }
+    }
+    for i in 0..=upper {
+        sum_s[i] += sum_l[i];
+        sum_l[i + 1] += sum_s[i];
+    }
+
+    let mut lms_map = vec![NONE; n + 1];
+    let mut lms = vec![];
+    for i in 1..n {
+        if !ls[i - 1] && ls[i] {
+            lms_map[i] = lms.len();
+            lms.push(i);
+        }
+    }
+    let m = lms.len();
+    let mut sa = vec![NONE; n];
+    induce(s, &ls, &sum_s, &sum_l, &lms, &mut sa);
+
+    if m > 0 {
+        let mut sorted_lms = sa
+            .iter()
+            .copied()
+            .filter(|&v| lms_map[v] != NONE)
+            .collect::<Vec<_>>();
+        let mut rec_s = vec![0; m];
+        let mut rec_upper = 0;
+        for i in 1..m {
+            let (mut l, mut r) = (sorted_lms[i - 1], sorted_lms[i]);
+            let end_l = if lms_map[l] + 1 < m {
+                lms[lms_map[l] + 1]
+            } else {
+                n
+            };
+            let end_r = if lms_map[r] + 1 < m {
+                lms[lms_map[r] + 1]
+            } else {
+                n
+            };
+            let mut same = end_l - l == end_r - r;
+            if same {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+                if l == n || r == n || s[l] != s[r] {
+                    same = false;
+                }
+            }
+            if !same {
+                rec_upper += 1;
+            }
+            rec_s[lms_map[sorted_lms[i]]] = rec_upper;
+        }
+        let rec_sa = sa_is(&rec_s, rec_upper);
+        for i in 0..m {
+            sorted_lms[i] = lms[rec_sa[i]];
+        }
+        induce(s, &ls, &sum_s, &sum_l, &sorted_lms, &mut sa);
+    }
+    sa
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Uniform;
+    use rand::Rng;
+
+    fn naive_sa<T: Ord>(s: &[T]) -> Vec<usize> {
+        let mut sa = (0..s.len()).collect::<Vec<_>>();
+        sa.sort_by(|&a, &b| s[a..].cmp(&s[b..]));
+        sa
+    }
+
+    fn naive_lcp<T: Eq>(s: &[T], i: usize, j: usize) -> usize {
+        s[i..]
+            .iter()
+            .zip(&s[j..])
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    #[test]
+    fn test_suffix_array_str() {
+        let sa: SuffixArray<u8> = "abracadabra".into();
+        assert_eq!(sa.sa(), &[10, 7, 0, 3, 5, 8, 1, 4, 6, 9, 2]);
+        for (i, &p) in sa.sa().iter().enumerate() {
+            assert_eq!(sa.rank()[p], i);
+        }
+        let sa: SuffixArray<u8> = "".into();
+        assert!(sa.is_empty());
+    }
+
+    #[test]
+    fn test_suffix_array_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..500 {
+            let n = rng.sample(Uniform::from(1..200));
+            let k = rng.sample(Uniform::from(1..=4u8));
+            let s = (0..n)
+                .map(|_| b'a' + rng.sample(Uniform::from(0..k)))
+                .collect::<Vec<_>>();
+            let sa = SuffixArray::from(&s);
+            assert_eq!(sa.sa(), naive_sa(&s).as_slice());
+        }
+    }
+
+    #[test]
+    fn test_suffix_array_generic() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.sample(Uniform::from(1..200));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(-1_000_000_000_000i64..=-999_999_999_997)))
+                .collect::<Vec<_>>();
+            let sa = SuffixArray::new(&s);
+            assert_eq!(sa.sa(), naive_sa(&s).as_slice());
+        }
+    }
+
+    #[test]
+    fn test_lcp_array() {
+        let sa: SuffixArray<u8> = "abracadabra".into();
+        assert_eq!(sa.lcp_array(), &[1, 4, 1, 1, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(sa.lcp(0, 7), 4);
+        assert_eq!(sa.lcp(3, 3), 8);
+    }
+
+    #[test]
+    fn test_lcp_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.sample(Uniform::from(1..100));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'c')))
+                .collect::<Vec<_>>();
+            let sa = SuffixArray::from(&s);
+            for i in 0..n - 1 {
+                assert_eq!(sa.lcp_array()[i], naive_lcp(&s, sa.sa()[i], sa.sa()[i + 1]));
+            }
+            for i in 0..n {
+                for j in 0..n {
+                    assert_eq!(sa.lcp(i, j), naive_lcp(&s, i, j));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_all() {
+        let sa: SuffixArray<u8> =
+            "unvhusmjlvieloveuybouqvnqjygutqlovedkfsdfgheaiuloveaeiuvaygayfg".into();
+        assert_eq!(sa.find_all("love"), vec![12, 31, 47]);
+        assert_eq!(sa.count("love"), 3);
+        assert_eq!(sa.count("u"), 7);
+        assert!(sa.contains("fg"));
+        assert!(!sa.contains("hate"));
+        assert_eq!(sa.count(""), sa.len());
+    }
+
+    #[test]
+    fn test_find_all_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.sample(Uniform::from(1..100));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(0..3i32)))
+                .collect::<Vec<_>>();
+            let sa = SuffixArray::new(&s);
+            for _ in 0..10 {
+                let m = rng.sample(Uniform::from(1..5));
+                let t = (0..m)
+                    .map(|_| rng.sample(Uniform::from(0..3i32)))
+                    .collect::<Vec<_>>();
+                let expected = (0..n)
+                    .filter(|&i| s[i..].starts_with(&t))
+                    .collect::<Vec<_>>();
+                assert_eq!(sa.find_all(&t), expected);
+                assert_eq!(sa.count(&t), expected.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_statistics() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = rng.sample(Uniform::from(1..60));
+            let s = (0..n)
+                .map(|_| rng.sample(Uniform::from(b'a'..=b'c')))
+                .collect::<Vec<_>>();
+            let sa = SuffixArray::from(&s);
+            let mut subs = std::collections::HashSet::new();
+            for i in 0..n {
+                for j in i + 1..=n {
+                    subs.insert(&s[i..j]);
+                }
+            }
+            assert_eq!(sa.distinct_substrings(), subs.len());
+            let mut best = 0;
+            for i in 0..n {
+                for j in i + 1..n {
+                    best = best.max(naive_lcp(&s, i, j));
+                }
+            }
+            let r = sa.longest_repeated_substring();
+            assert_eq!(r.len(), best);
+            if best > 0 {
+                assert!(sa.count(&s[r]) >= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_longest_common_substring() {
+        let (ra, rb) = SuffixArray::longest_common_substring(b"abcdxyz", b"xyzabcq");
+        assert_eq!((ra, rb), (0..3, 3..6));
+        let (ra, rb) = SuffixArray::longest_common_substring(b"aaa", b"bbb");
+        assert_eq!((ra.len(), rb.len()), (0, 0));
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let gen = |rng: &mut rand::rngs::ThreadRng| {
+                let n = rng.sample(Uniform::from(1..30));
+                (0..n)
+                    .map(|_| rng.sample(Uniform::from(0..3u64)))
+                    .collect::<Vec<_>>()
+            };
+            let (a, b) = (gen(&mut rng), gen(&mut rng));
+            let mut best = 0;
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    let h = a[i..].iter().zip(&b[j..]).take_while(|(x, y)| x == y);
+                    best = best.max(h.count());
+                }
+            }
+            let (ra, rb) = SuffixArray::longest_common_substring(&a, &b);
+            assert_eq!(ra.len(), best);
+            assert_eq!(a[ra], b[rb]);
+        }
+    }
+}
@@ -1,6 +1,12 @@
+extern crate __procon_math_traits as math_traits;
+
+use math_traits::{AbelianGroup, Monoid};
+use std::{collections::BTreeMap, rc::Rc};
+
 pub struct UnionFind {
     parent: Vec<usize>,
     sizes: Vec<usize>,
+    next: Vec<usize>,
     size: usize,
 }
 
@@ -9,6 +15,7 @@ impl UnionFind {
         Self {
             parent: (0..n).collect(),
             sizes: vec![1; n],
+            next: (0..n).collect(),
             size: n,
         }
     }
@@ -37,6 +44,7 @@ impl UnionFind {
         self.parent[x] = y;
         self.sizes[y] += self.sizes[x];
         self.sizes[x] = 0;
+        self.next.swap(x, y);
         self.size -= 1;
         true
     }
@@ -55,11 +63,631 @@ impl UnionFind {
     pub fn len(&self) -> usize {
         self.size
     }
+
+    /// Members of the component containing `x`, starting from `x`.
+    pub fn members(&self, x: usize) -> Members<'_> {
+        Members {
+            next: &self.next,
+            start: x,
+            cur: Some(x),
+        }
+    }
+
+    /// All components, ordered by their smallest member.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.parent.len();
+        let mut index = vec![usize::MAX; n];
+        let mut res: Vec<Vec<usize>> = vec![];
+        for x in 0..n {
+            let r = self.root(x);
+            if index[r] == usize::MAX {
+                index[r] = res.len();
+                res.push(Vec::with_capacity(self.sizes[r]));
+            }
+            res[index[r]].push(x);
+        }
+        res
+    }
+}
+
+pub struct Members<'a> {
+    next: &'a [usize],
+    start: usize,
+    cur: Option<usize>,
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let x = self.cur?;
+        let y = self.next[x];
+        self.cur = if y == self.start { None } else { Some(y) };
+        Some(x)
+    }
+}
+
+/// Union-find where each component carries the `M::op` of its members' values.
+pub struct UnionFindWith<M: Monoid> {
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    data: Vec<M::T>,
+    size: usize,
+}
+
+impl<M: Monoid> UnionFindWith<M> {
+    pub fn new(values: Vec<M::T>) -> Self {
+        let n = values.len();
+        Self {
+            parent: (0..n).collect(),
+            sizes: vec![1; n],
+            data: values,
+            size: n,
+        }
+    }
+
+    pub fn root(&mut self, x: usize) -> usize {
+        if x == self.parent[x] {
+            x
+        } else {
+            let p = self.parent[x];
+            self.parent[x] = self.root(p);
+            self.parent[x]
+        }
+    }
+
+    /// Merges the aggregates of both components. `M` is assumed to be commutative.
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.root(x), self.root(y));
+        if x == y {
+            return false;
+        }
+        let (x, y) = if self.sizes[x] > self.sizes[y] {
+            (y, x)
+        } else {
+            (x, y)
+        };
+        self.parent[x] = y;
+        self.sizes[y] += self.sizes[x];
+        self.sizes[x] = 0;
+        self.data[y] = M::op(&self.data[y], &self.data[x]);
+        self.size -= 1;
+        true
+    }
+
+    pub fn is_same(&mut self, x: usize, y: usize) -> bool {
+        self.root(x) == self.root(y)
+    }
+
+    pub fn group_size(&mut self, x: usize) -> usize {
+        let r = self.root(x);
+        self.sizes[r]
+    }
+
+    /// Aggregate of the component containing `x`.
+    pub fn get(&mut self, x: usize) -> &M::T {
+        let r = self.root(x);
+        &self.data[r]
+    }
+
+    /// Folds `v` into the aggregate of the component containing `x`.
+    pub fn apply(&mut self, x: usize, v: &M::T) {
+        let r = self.root(x);
+        self.data[r] = M::op(&self.data[r], v);
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+pub struct WeightedUnionFind<G: AbelianGroup> {
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    weight: Vec<G::T>,
+    size: usize,
+}
+
+impl<G> WeightedUnionFind<G>
+where
+    G: AbelianGroup,
+    G::T: PartialEq,
+{
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            sizes: vec![1; n],
+            weight: vec![G::id(); n],
+            size: n,
+        }
+    }
+
+    pub fn root(&mut self, x: usize) -> usize {
+        if x == self.parent[x] {
+            x
+        } else {
+            let p = self.parent[x];
+            let r = self.root(p);
+            self.weight[x] = G::op(&self.weight[x], &self.weight[p]);
+            self.parent[x] = r;
+            r
+        }
+    }
+
+    /// Potential of `x` relative to the root of its group.
+    pub fn potential(&mut self, x: usize) -> G::T {
+        self.root(x);
+        self.weight[x].clone()
+    }
+
+    /// Adds the constraint `pot(y) - pot(x) = w`.
+    /// Returns `Err` with the already known `pot(y) - pot(x)` if it contradicts.
+    pub fn unite(&mut self, x: usize, y: usize, w: G::T) -> Result<bool, G::T> {
+        let (wx, wy) = (self.potential(x), self.potential(y));
+        let (rx, ry) = (self.root(x), self.root(y));
+        if rx == ry {
+            let d = G::op(&wy, &G::inv(&wx));
+            return if d == w { Ok(false) } else { Err(d) };
+        }
+        // pot(rx) - pot(ry)
+        let d = G::op(&wy, &G::inv(&G::op(&w, &wx)));
+        let (x, y, d) = if self.sizes[rx] > self.sizes[ry] {
+            (ry, rx, G::inv(&d))
+        } else {
+            (rx, ry, d)
+        };
+        self.parent[x] = y;
+        self.weight[x] = d;
+        self.sizes[y] += self.sizes[x];
+        self.sizes[x] = 0;
+        self.size -= 1;
+        Ok(true)
+    }
+
+    /// `pot(y) - pot(x)` if `x` and `y` are connected.
+    pub fn diff(&mut self, x: usize, y: usize) -> Option<G::T> {
+        if self.is_same(x, y) {
+            Some(G::op(&self.weight[y], &G::inv(&self.weight[x])))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_same(&mut self, x: usize, y: usize) -> bool {
+        self.root(x) == self.root(y)
+    }
+
+    pub fn group_size(&mut self, mut x: usize) -> usize {
+        x = self.root(x);
+        self.sizes[x]
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+/// Union-find without path compression whose merges can be undone.
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    sizes: Vec<usize>,
+    size: usize,
+    history: Vec<(usize, usize)>,
+}
+
+impl RollbackUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            sizes: vec![1; n],
+            size: n,
+            history: vec![],
+        }
+    }
+
+    pub fn root(&self, mut x: usize) -> usize {
+        while x != self.parent[x] {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.root(x), self.root(y));
+        if x == y {
+            return false;
+        }
+        let (x, y) = if self.sizes[x] > self.sizes[y] {
+            (y, x)
+        } else {
+            (x, y)
+        };
+        self.parent[x] = y;
+        self.sizes[y] += self.sizes[x];
+        self.size -= 1;
+        self.history.push((x, y));
+        true
+    }
+
+    pub fn is_same(&self, x: usize, y: usize) -> bool {
+        self.root(x) == self.root(y)
+    }
+
+    pub fn group_size(&self, x: usize) -> usize {
+        self.sizes[self.root(x)]
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of merges performed so far, to be passed to `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the last successful `unite`. Returns `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((x, y)) => {
+                self.parent[x] = x;
+                self.sizes[y] -= self.sizes[x];
+                self.size += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Undoes merges until the state taken by `snapshot` is restored.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectivityOp {
+    AddEdge(usize, usize),
+    RemoveEdge(usize, usize),
+    IsSame(usize, usize),
+}
+
+/// Answers every `IsSame` in `ops` offline with a segment tree over time.
+/// O(Q log Q log N)
+pub fn dynamic_connectivity(n: usize, ops: &[ConnectivityOp]) -> Vec<bool> {
+    use ConnectivityOp::*;
+    let q = ops.len();
+    let mut size = 1;
+    while size < q {
+        size <<= 1;
+    }
+    let mut edges = vec![vec![]; 2 * size];
+    let mut alive: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    let mut add = |mut l: usize, mut r: usize, e: (usize, usize)| {
+        l += size;
+        r += size;
+        while l < r {
+            if l & 1 == 1 {
+                edges[l].push(e);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                edges[r].push(e);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+    };
+    for (t, op) in ops.iter().enumerate() {
+        match *op {
+            AddEdge(u, v) => alive.entry((u.min(v), u.max(v))).or_default().push(t),
+            RemoveEdge(u, v) => {
+                let e = (u.min(v), u.max(v));
+                let s = alive
+                    .get_mut(&e)
+                    .and_then(|s| s.pop())
+                    .expect("removed edge does not exist");
+                add(s, t, e);
+            }
+            IsSame(..) => {}
+        }
+    }
+    for (&e, starts) in alive.iter() {
+        for &s in starts {
+            add(s, q, e);
+        }
+    }
+    let mut uf = RollbackUnionFind::new(n);
+    let mut res = vec![None; q];
+    let mut stack = vec![(1, None)];
+    while let Some((k, snapshot)) = stack.pop() {
+        if let Some(snapshot) = snapshot {
+            uf.rollback(snapshot);
+            continue;
+        }
+        stack.push((k, Some(uf.snapshot())));
+        for &(u, v) in &edges[k] {
+            uf.unite(u, v);
+        }
+        if k >= size {
+            if let Some(&IsSame(u, v)) = ops.get(k - size) {
+                res[k - size] = Some(uf.is_same(u, v));
+            }
+        } else {
+            stack.push((2 * k + 1, None));
+            stack.push((2 * k, None));
+        }
+    }
+    res.into_iter().flatten().collect()
+}
+
+/// Union-find that can answer queries about any past state.
+/// Time `t` is the state after the first `t` calls of `unite`.
+pub struct PartiallyPersistentUnionFind {
+    parent: Vec<usize>,
+    time: Vec<usize>,
+    sizes: Vec<Vec<(usize, usize)>>,
+    now: usize,
+    size: usize,
+}
+
+impl PartiallyPersistentUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            time: vec![usize::MAX; n],
+            sizes: vec![vec![(0, 1)]; n],
+            now: 0,
+            size: n,
+        }
+    }
+
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    pub fn root_at(&self, mut x: usize, t: usize) -> usize {
+        while self.time[x] <= t {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn root(&self, x: usize) -> usize {
+        self.root_at(x, self.now)
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        self.now += 1;
+        let (x, y) = (self.root(x), self.root(y));
+        if x == y {
+            return false;
+        }
+        let (sx, sy) = (self.group_size(x), self.group_size(y));
+        let (x, y) = if sx > sy { (y, x) } else { (x, y) };
+        self.parent[x] = y;
+        self.time[x] = self.now;
+        self.sizes[y].push((self.now, sx + sy));
+        self.size -= 1;
+        true
+    }
+
+    pub fn is_same_at(&self, x: usize, y: usize, t: usize) -> bool {
+        self.root_at(x, t) == self.root_at(y, t)
+    }
+
+    pub fn is_same(&self, x: usize, y: usize) -> bool {
+        self.is_same_at(x, y, self.now)
+    }
+
+    pub fn group_size_at(&self, x: usize, t: usize) -> usize {
+        let sizes = &self.sizes[self.root_at(x, t)];
+        let i = sizes.partition_point(|&(s, _)| s <= t);
+        sizes[i - 1].1
+    }
+
+    pub fn group_size(&self, x: usize) -> usize {
+        self.group_size_at(x, self.now)
+    }
+
+    /// Smallest `t` such that `x` and `y` are connected at time `t`.
+    pub fn first_connected_time(&self, x: usize, y: usize) -> Option<usize> {
+        if !self.is_same(x, y) {
+            return None;
+        }
+        if x == y {
+            return Some(0);
+        }
+        let (mut ng, mut ok) = (0, self.now);
+        while ok - ng > 1 {
+            let mid = (ng + ok) / 2;
+            if self.is_same_at(x, y, mid) {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        Some(ok)
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+enum ArrayNode<T> {
+    Leaf(T),
+    Branch(Rc<ArrayNode<T>>, Rc<ArrayNode<T>>),
+}
+
+/// Array whose updates return a new version sharing unchanged nodes.
+struct PersistentArray<T> {
+    len: usize,
+    root: Option<Rc<ArrayNode<T>>>,
+}
+
+impl<T> Clone for PersistentArray<T> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T: Clone> PersistentArray<T> {
+    fn new(values: &[T]) -> Self {
+        fn build<T: Clone>(values: &[T]) -> Rc<ArrayNode<T>> {
+            if values.len() == 1 {
+                Rc::new(ArrayNode::Leaf(values[0].clone()))
+            } else {
+                let (l, r) = values.split_at(values.len() / 2);
+                Rc::new(ArrayNode::Branch(build(l), build(r)))
+            }
+        }
+        Self {
+            len: values.len(),
+            root: if values.is_empty() {
+                None
+            } else {
+                Some(build(values))
+            },
+        }
+    }
+
+    fn get(&self, mut i: usize) -> &T {
+        assert!(i < self.len);
+        let (mut node, mut len) = (self.root.as_ref().unwrap(), self.len);
+        loop {
+            match node.as_ref() {
+                ArrayNode::Leaf(v) => return v,
+                ArrayNode::Branch(l, r) => {
+                    let half = len / 2;
+                    if i < half {
+                        node = l;
+                        len = half;
+                    } else {
+                        node = r;
+                        i -= half;
+                        len -= half;
+                    }
+                }
+            }
+        }
+    }
+
+    fn set(&self, i: usize, v: T) -> Self {
+        fn set<T>(node: &ArrayNode<T>, i: usize, len: usize, v: T) -> Rc<ArrayNode<T>> {
+            match node {
+                ArrayNode::Leaf(_) => Rc::new(ArrayNode::Leaf(v)),
+                ArrayNode::Branch(l, r) => {
+                    let half = len / 2;
+                    if i < half {
+                        Rc::new(ArrayNode::Branch(set(l, i, half, v), r.clone()))
+                    } else {
+                        Rc::new(ArrayNode::Branch(
+                            l.clone(),
+                            set(r, i - half, len - half, v),
+                        ))
+                    }
+                }
+            }
+        }
+        assert!(i < self.len);
+        Self {
+            len: self.len,
+            root: Some(set(self.root.as_ref().unwrap(), i, self.len, v)),
+        }
+    }
+}
+
+/// Fully persistent union-find. `unite` leaves `self` untouched and returns the new version.
+#[derive(Clone)]
+pub struct PersistentUnionFind {
+    parent: PersistentArray<usize>,
+    sizes: PersistentArray<usize>,
+    size: usize,
+}
+
+impl PersistentUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: PersistentArray::new(&(0..n).collect::<Vec<_>>()),
+            sizes: PersistentArray::new(&vec![1; n]),
+            size: n,
+        }
+    }
+
+    pub fn root(&self, mut x: usize) -> usize {
+        loop {
+            let p = *self.parent.get(x);
+            if p == x {
+                return x;
+            }
+            x = p;
+        }
+    }
+
+    /// Returns the new version and whether two components were merged.
+    pub fn unite(&self, x: usize, y: usize) -> (Self, bool) {
+        let (x, y) = (self.root(x), self.root(y));
+        if x == y {
+            return (self.clone(), false);
+        }
+        let (sx, sy) = (*self.sizes.get(x), *self.sizes.get(y));
+        let (x, y) = if sx > sy { (y, x) } else { (x, y) };
+        let uf = Self {
+            parent: self.parent.set(x, y),
+            sizes: self.sizes.set(y, sx + sy),
+            size: self.size - 1,
+        };
+        (uf, true)
+    }
+
+    pub fn is_same(&self, x: usize, y: usize) -> bool {
+        self.root(x) == self.root(y)
+    }
+
+    pub fn group_size(&self, x: usize) -> usize {
+        *self.sizes.get(self.root(x))
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    extern crate __procon_modint as modint;
+
     use super::*;
+    use math_traits::{Additive, Maximum, Minimum, Xor};
+    use modint::ModInt998244353;
+    use rand::Rng;
 
     #[test]
     fn test_unionfind() {
@@ -80,4 +708,220 @@ mod tests {
         assert_eq!(uf.group_size(3), 5);
         assert_eq!(uf.len(), 1);
     }
+
+    #[test]
+    fn test_groups_members() {
+        let mut uf = UnionFind::new(6);
+        uf.unite(4, 1);
+        uf.unite(0, 5);
+        uf.unite(1, 3);
+        assert_eq!(uf.groups(), vec![vec![0, 5], vec![1, 3, 4], vec![2]]);
+        let mut m = uf.members(3).collect::<Vec<_>>();
+        assert_eq!(m[0], 3);
+        m.sort_unstable();
+        assert_eq!(m, vec![1, 3, 4]);
+        assert_eq!(uf.members(2).collect::<Vec<_>>(), vec![2]);
+
+        let mut rng = rand::thread_rng();
+        let n = 30;
+        let mut uf = UnionFind::new(n);
+        for _ in 0..40 {
+            uf.unite(rng.gen_range(0, n), rng.gen_range(0, n));
+            let x = rng.gen_range(0, n);
+            let mut m = uf.members(x).collect::<Vec<_>>();
+            m.sort_unstable();
+            let expected = (0..n).filter(|&y| uf.is_same(x, y)).collect::<Vec<_>>();
+            assert_eq!(m, expected);
+            assert_eq!(uf.groups().iter().map(|g| g.len()).sum::<usize>(), n);
+        }
+    }
+
+    #[test]
+    fn test_unionfind_with() {
+        let values = vec![5, 3, 8, 1, 9];
+        let mut sum = UnionFindWith::<Additive<i64>>::new(values.clone());
+        let mut min = UnionFindWith::<Minimum<i64>>::new(values.clone());
+        let mut max = UnionFindWith::<Maximum<i64>>::new(values);
+        for &(x, y) in &[(0, 1), (3, 4), (1, 0), (2, 1)] {
+            sum.unite(x, y);
+            min.unite(x, y);
+            max.unite(x, y);
+        }
+        assert_eq!(*sum.get(0), 16);
+        assert_eq!(*sum.get(4), 10);
+        assert_eq!(*min.get(2), 3);
+        assert_eq!(*max.get(3), 9);
+        assert_eq!(sum.group_size(1), 3);
+        assert_eq!(sum.len(), 2);
+        sum.apply(4, &5);
+        assert_eq!(*sum.get(3), 15);
+        sum.unite(0, 4);
+        assert_eq!(*sum.get(2), 31);
+    }
+
+    #[test]
+    fn test_weighted_unionfind() {
+        let mut uf = WeightedUnionFind::<Additive<i64>>::new(4);
+        assert_eq!(uf.unite(0, 1, 3), Ok(true));
+        assert_eq!(uf.unite(1, 2, -5), Ok(true));
+        assert_eq!(uf.diff(0, 2), Some(-2));
+        assert_eq!(uf.diff(2, 0), Some(2));
+        assert_eq!(uf.diff(0, 3), None);
+        assert_eq!(uf.unite(2, 0, 2), Ok(false));
+        assert_eq!(uf.unite(2, 0, 1), Err(2));
+        assert_eq!(uf.len(), 2);
+
+        let mut uf = WeightedUnionFind::<Xor<u32>>::new(3);
+        assert_eq!(uf.unite(0, 1, 0b101), Ok(true));
+        assert_eq!(uf.unite(2, 1, 0b011), Ok(true));
+        assert_eq!(uf.diff(0, 2), Some(0b110));
+        assert_eq!(uf.unite(2, 0, 0b111), Err(0b110));
+
+        let mut rng = rand::thread_rng();
+        let n = 50;
+        let pot = (0..n)
+            .map(|_| ModInt998244353::new(rng.gen_range(0, 998244353)))
+            .collect::<Vec<_>>();
+        let mut uf = WeightedUnionFind::<Additive<ModInt998244353>>::new(n);
+        let mut naive = UnionFind::new(n);
+        for _ in 0..100 {
+            let (x, y) = (rng.gen_range(0, n), rng.gen_range(0, n));
+            assert_eq!(uf.unite(x, y, pot[y] - pot[x]), Ok(naive.unite(x, y)));
+            if x != y {
+                assert!(uf.unite(x, y, pot[y] - pot[x] + 1).is_err());
+            }
+            let (x, y) = (rng.gen_range(0, n), rng.gen_range(0, n));
+            let expected = if naive.is_same(x, y) {
+                Some(pot[y] - pot[x])
+            } else {
+                None
+            };
+            assert_eq!(uf.diff(x, y), expected);
+        }
+    }
+
+    #[test]
+    fn test_rollback_unionfind() {
+        let mut uf = RollbackUnionFind::new(5);
+        uf.unite(0, 1);
+        let s = uf.snapshot();
+        assert!(uf.unite(1, 2));
+        assert!(!uf.unite(0, 2));
+        assert!(uf.unite(3, 4));
+        assert_eq!(uf.group_size(2), 3);
+        assert_eq!(uf.len(), 2);
+        assert!(uf.undo());
+        assert!(!uf.is_same(3, 4));
+        uf.rollback(s);
+        assert!(uf.is_same(0, 1));
+        assert!(!uf.is_same(1, 2));
+        assert_eq!(uf.group_size(0), 2);
+        assert_eq!(uf.len(), 4);
+        assert!(uf.undo());
+        assert!(!uf.undo());
+        assert_eq!(uf.len(), 5);
+    }
+
+    #[test]
+    fn test_dynamic_connectivity() {
+        use ConnectivityOp::*;
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let n = rng.gen_range(1, 10);
+            let mut edges: Vec<(usize, usize)> = vec![];
+            let mut ops = vec![];
+            let mut expected = vec![];
+            for _ in 0..rng.gen_range(0, 200) {
+                let (u, v) = (rng.gen_range(0, n), rng.gen_range(0, n));
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        edges.push((u, v));
+                        ops.push(AddEdge(u, v));
+                    }
+                    1 if !edges.is_empty() => {
+                        let (u, v) = edges.swap_remove(rng.gen_range(0, edges.len()));
+                        ops.push(RemoveEdge(v, u));
+                    }
+                    _ => {
+                        let mut uf = UnionFind::new(n);
+                        for &(a, b) in &edges {
+                            uf.unite(a, b);
+                        }
+                        expected.push(uf.is_same(u, v));
+                        ops.push(IsSame(u, v));
+                    }
+                }
+            }
+            assert_eq!(dynamic_connectivity(n, &ops), expected);
+        }
+    }
+
+    #[test]
+    fn test_partially_persistent_unionfind() {
+        let mut rng = rand::thread_rng();
+        let n = 20;
+        let mut uf = PartiallyPersistentUnionFind::new(n);
+        let mut edges = vec![];
+        for _ in 0..40 {
+            let (x, y) = (rng.gen_range(0, n), rng.gen_range(0, n));
+            edges.push((x, y));
+            uf.unite(x, y);
+        }
+        assert_eq!(uf.now(), edges.len());
+        let mut naive = UnionFind::new(n);
+        let mut first = vec![vec![None; n]; n];
+        for t in 0..=edges.len() {
+            if t > 0 {
+                naive.unite(edges[t - 1].0, edges[t - 1].1);
+            }
+            for (x, first) in first.iter_mut().enumerate() {
+                assert_eq!(uf.group_size_at(x, t), naive.group_size(x));
+                for (y, first) in first.iter_mut().enumerate() {
+                    assert_eq!(uf.is_same_at(x, y, t), naive.is_same(x, y));
+                    if naive.is_same(x, y) && first.is_none() {
+                        *first = Some(t);
+                    }
+                }
+            }
+        }
+        assert_eq!(uf.len(), naive.len());
+        for (x, first) in first.iter().enumerate() {
+            for (y, &first) in first.iter().enumerate() {
+                assert_eq!(uf.first_connected_time(x, y), first);
+            }
+        }
+    }
+
+    #[test]
+    fn test_persistent_unionfind() {
+        let mut rng = rand::thread_rng();
+        let n = 15;
+        let mut versions = vec![(PersistentUnionFind::new(n), vec![])];
+        for _ in 0..100 {
+            let v = rng.gen_range(0, versions.len());
+            let (x, y) = (rng.gen_range(0, n), rng.gen_range(0, n));
+            let (uf, merged) = versions[v].0.unite(x, y);
+            let mut edges = versions[v].1.clone();
+            let mut naive = UnionFind::new(n);
+            for &(a, b) in &edges {
+                naive.unite(a, b);
+            }
+            assert_eq!(merged, naive.unite(x, y));
+            edges.push((x, y));
+            versions.push((uf, edges));
+        }
+        for (uf, edges) in &versions {
+            let mut naive = UnionFind::new(n);
+            for &(x, y) in edges {
+                naive.unite(x, y);
+            }
+            assert_eq!(uf.len(), naive.len());
+            for x in 0..n {
+                assert_eq!(uf.group_size(x), naive.group_size(x));
+                for y in 0..n {
+                    assert_eq!(uf.is_same(x, y), naive.is_same(x, y));
+                }
+            }
+        }
+    }
 }